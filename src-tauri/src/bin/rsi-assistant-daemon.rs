@@ -0,0 +1,4 @@
+// Headless break timer sharing settings and statistics with the app
+fn main() {
+    std::process::exit(rsi_assistant_lib::daemon::run());
+}
@@ -0,0 +1,287 @@
+//! Contexts a break prompt should not interrupt, found on the session bus: an
+//! MPRIS player that is playing, or an idle inhibitor held with the session
+//! manager, as video players and presentation tools take.
+
+use crate::timer::DeferConfig;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use zbus::names::OwnedBusName;
+use zbus::proxy::CacheProperties;
+use zbus::{fdo, Connection};
+
+const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+// GSM_INHIBITOR_FLAG_IDLE
+const GNOME_INHIBIT_IDLE: u32 = 8;
+
+/// Seconds between checks, which take several calls on the bus.
+pub const CHECK_INTERVAL_SECS: u64 = 5;
+
+// A check still unanswered after this long counts as not busy
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[zbus::proxy(
+    interface = "org.mpris.MediaPlayer2.Player",
+    default_path = "/org/mpris/MediaPlayer2"
+)]
+trait Player {
+    #[zbus(property)]
+    fn playback_status(&self) -> zbus::Result<String>;
+}
+
+#[zbus::proxy(
+    interface = "org.gnome.SessionManager",
+    default_service = "org.gnome.SessionManager",
+    default_path = "/org/gnome/SessionManager"
+)]
+trait GnomeSessionManager {
+    fn is_inhibited(&self, flags: u32) -> zbus::Result<bool>;
+}
+
+// Implemented by KDE and Xfce, which route screensaver inhibitors through it
+#[zbus::proxy(
+    interface = "org.freedesktop.PowerManagement.Inhibit",
+    default_service = "org.freedesktop.PowerManagement",
+    default_path = "/org/freedesktop/PowerManagement/Inhibit"
+)]
+trait PowerManagementInhibit {
+    fn has_inhibit(&self) -> zbus::Result<bool>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BusyReason {
+    MediaPlaying,
+    IdleInhibited,
+}
+
+pub struct BusyDetector {
+    connection: Connection,
+    dbus: fdo::DBusProxy<'static>,
+    gnome: GnomeSessionManagerProxy<'static>,
+    power: PowerManagementInhibitProxy<'static>,
+    // By bus name, dropped once the player leaves the bus
+    players: Mutex<HashMap<OwnedBusName, PlayerProxy<'static>>>,
+}
+
+impl BusyDetector {
+    pub async fn connect() -> zbus::Result<Self> {
+        Self::with_connection(Connection::session().await?).await
+    }
+
+    pub async fn with_connection(connection: Connection) -> zbus::Result<Self> {
+        Ok(Self {
+            dbus: fdo::DBusProxy::new(&connection).await?,
+            gnome: GnomeSessionManagerProxy::new(&connection).await?,
+            power: PowerManagementInhibitProxy::new(&connection).await?,
+            players: Mutex::new(HashMap::new()),
+            connection,
+        })
+    }
+
+    /// Why a due break should wait, if it should. Services missing from the
+    /// session count as not busy.
+    pub async fn check(&self, config: &DeferConfig) -> Option<BusyReason> {
+        if config.inhibitors && self.idle_inhibited().await {
+            return Some(BusyReason::IdleInhibited);
+        }
+        if config.media && self.media_playing().await {
+            return Some(BusyReason::MediaPlaying);
+        }
+        None
+    }
+
+    async fn media_playing(&self) -> bool {
+        let Ok(names) = self.dbus.list_names().await else { return false };
+        let names: Vec<_> =
+            names.into_iter().filter(|name| name.starts_with(MPRIS_PREFIX)).collect();
+        self.players.lock().unwrap().retain(|name, _| names.contains(name));
+
+        for name in names {
+            if self.is_playing(name).await.unwrap_or(false) {
+                return true;
+            }
+        }
+        false
+    }
+
+    async fn is_playing(&self, name: OwnedBusName) -> zbus::Result<bool> {
+        let cached = self.players.lock().unwrap().get(&name).cloned();
+        let player = match cached {
+            Some(player) => player,
+            None => {
+                let player = PlayerProxy::builder(&self.connection)
+                    .destination(name.clone())?
+                    .cache_properties(CacheProperties::No)
+                    .build()
+                    .await?;
+                self.players.lock().unwrap().insert(name, player.clone());
+                player
+            }
+        };
+        Ok(player.playback_status().await? == "Playing")
+    }
+
+    async fn idle_inhibited(&self) -> bool {
+        if self.gnome.is_inhibited(GNOME_INHIBIT_IDLE).await.unwrap_or(false) {
+            return true;
+        }
+        self.power.has_inhibit().await.unwrap_or(false)
+    }
+}
+
+/// Runs the detector's checks in the background, so a slow or unresponsive
+/// service on the bus never holds up the tick.
+pub struct BusyMonitor {
+    detector: Arc<BusyDetector>,
+    busy: Arc<AtomicBool>,
+    pending: Option<JoinHandle<()>>,
+}
+
+impl BusyMonitor {
+    pub fn new(detector: BusyDetector) -> Self {
+        Self { detector: Arc::new(detector), busy: Arc::new(AtomicBool::new(false)), pending: None }
+    }
+
+    /// Starts a check unless the last one is still running. Must be called
+    /// from within the Tokio runtime.
+    pub fn start_check(&mut self, config: DeferConfig) {
+        if self.pending.as_ref().is_some_and(|pending| !pending.is_finished()) {
+            return;
+        }
+        let detector = self.detector.clone();
+        let busy = self.busy.clone();
+        self.pending = Some(tokio::spawn(async move {
+            let reason = tokio::time::timeout(CHECK_TIMEOUT, detector.check(&config)).await;
+            busy.store(matches!(reason, Ok(Some(_))), Ordering::Relaxed);
+        }));
+    }
+
+    /// The answer of the last check to finish.
+    pub fn is_busy(&self) -> bool {
+        self.busy.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for BusyMonitor {
+    fn drop(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            pending.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbus::test_bus::PrivateBus;
+
+    struct StandInPlayer {
+        status: String,
+    }
+
+    #[zbus::interface(name = "org.mpris.MediaPlayer2.Player")]
+    impl StandInPlayer {
+        #[zbus(property)]
+        fn playback_status(&self) -> String {
+            self.status.clone()
+        }
+    }
+
+    struct StandInSessionManager {
+        inhibited: u32,
+    }
+
+    #[zbus::interface(name = "org.gnome.SessionManager")]
+    impl StandInSessionManager {
+        fn is_inhibited(&self, flags: u32) -> bool {
+            self.inhibited & flags != 0
+        }
+    }
+
+    // Never answers, as a hung session manager would
+    struct UnresponsiveSessionManager;
+
+    #[zbus::interface(name = "org.gnome.SessionManager")]
+    impl UnresponsiveSessionManager {
+        async fn is_inhibited(&self, _flags: u32) -> bool {
+            std::future::pending().await
+        }
+    }
+
+    async fn serve<I: zbus::object_server::Interface>(
+        bus: &PrivateBus,
+        name: &str,
+        path: &str,
+        interface: I,
+    ) -> Connection {
+        let connection = bus.connect().await;
+        connection.object_server().at(path, interface).await.unwrap();
+        connection.request_name(name).await.unwrap();
+        connection
+    }
+
+    #[tokio::test]
+    async fn test_media_and_inhibitors_on_private_bus() {
+        let Some(bus) = PrivateBus::start() else { return };
+        let detector = BusyDetector::with_connection(bus.connect().await).await.unwrap();
+        let config = DeferConfig { media: true, ..DeferConfig::default() };
+        assert_eq!(detector.check(&config).await, None);
+
+        let paused = StandInPlayer { status: "Paused".to_string() };
+        let _paused =
+            serve(&bus, "org.mpris.MediaPlayer2.music", "/org/mpris/MediaPlayer2", paused).await;
+        assert_eq!(detector.check(&config).await, None);
+
+        let playing = StandInPlayer { status: "Playing".to_string() };
+        let _playing =
+            serve(&bus, "org.mpris.MediaPlayer2.video", "/org/mpris/MediaPlayer2", playing).await;
+        assert_eq!(detector.check(&config).await, Some(BusyReason::MediaPlaying));
+        // Playback alone holds breaks back only when opted into
+        let media_off = DeferConfig::default();
+        assert_eq!(detector.check(&media_off).await, None);
+
+        // Inhibiting only logout does not hold breaks back
+        let logout = StandInSessionManager { inhibited: 1 };
+        let path = "/org/gnome/SessionManager";
+        let session = serve(&bus, "org.gnome.SessionManager", path, logout).await;
+        assert_eq!(detector.check(&media_off).await, None);
+
+        let manager =
+            session.object_server().interface::<_, StandInSessionManager>(path).await.unwrap();
+        manager.get_mut().await.inhibited = GNOME_INHIBIT_IDLE;
+        assert_eq!(detector.check(&config).await, Some(BusyReason::IdleInhibited));
+    }
+
+    async fn finish(monitor: &mut BusyMonitor) {
+        let pending = monitor.pending.take().unwrap();
+        tokio::time::timeout(CHECK_TIMEOUT * 2, pending).await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_monitor_checks_in_background() {
+        let Some(bus) = PrivateBus::start() else { return };
+        let detector = BusyDetector::with_connection(bus.connect().await).await.unwrap();
+        let mut monitor = BusyMonitor::new(detector);
+        let playing = StandInPlayer { status: "Playing".to_string() };
+        let _playing =
+            serve(&bus, "org.mpris.MediaPlayer2.video", "/org/mpris/MediaPlayer2", playing).await;
+
+        monitor.start_check(DeferConfig { media: true, ..DeferConfig::default() });
+        finish(&mut monitor).await;
+        assert!(monitor.is_busy());
+
+        // A service that never answers neither holds up the caller nor keeps the
+        // user busy once the check times out
+        let path = "/org/gnome/SessionManager";
+        let _session =
+            serve(&bus, "org.gnome.SessionManager", path, UnresponsiveSessionManager).await;
+        let started = std::time::Instant::now();
+        monitor.start_check(DeferConfig::default());
+        assert!(started.elapsed() < CHECK_TIMEOUT / 2);
+        assert!(monitor.is_busy());
+        finish(&mut monitor).await;
+        assert!(!monitor.is_busy());
+    }
+}
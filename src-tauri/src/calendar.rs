@@ -0,0 +1,592 @@
+//! Meetings from a local iCalendar file, or a directory of `.ics` files, as
+//! windows in which due breaks wait.
+//!
+//! Recurring events are expanded for `FREQ` `DAILY`, `WEEKLY` (with `BYDAY`),
+//! `MONTHLY` and `YEARLY`, with `INTERVAL`, `COUNT`, `UNTIL`, `EXDATE` and
+//! moved occurrences (`RECURRENCE-ID`). Times with a `TZID` are read in that
+//! zone and events repeat on its clock, across daylight saving changes; a
+//! `TZID` the tz database does not know is taken as local time. All-day, free
+//! (`TRANSP:TRANSPARENT`) and cancelled events are not meetings.
+
+use chrono::{
+    Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use ical::parser::ical::component::IcalEvent;
+use ical::property::Property;
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use tokio::sync::oneshot;
+
+/// How often the calendar is read again, in seconds.
+const RELOAD_INTERVAL_SECS: i64 = 300;
+/// How far ahead recurring events are expanded on each read, in seconds.
+const HORIZON_SECS: i64 = 86_400;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Meeting {
+    pub start: NaiveDateTime, // local time
+    pub end: NaiveDateTime,
+}
+
+impl Meeting {
+    pub fn contains(&self, time: NaiveDateTime) -> bool {
+        self.start <= time && time < self.end
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<NaiveDateTime>,
+    by_day: Vec<Weekday>, // weekly rules only
+}
+
+/// The zone a date-time is written in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Zone {
+    Floating, // no zone, or one not in the tz database: local time
+    Utc,
+    Named(Tz),
+}
+
+impl Zone {
+    fn of(property: &Property) -> Self {
+        if property.value.as_deref().is_some_and(|value| value.ends_with('Z')) {
+            return Zone::Utc;
+        }
+        let tzid = property.params.iter().flatten().find(|(name, _)| name == "TZID");
+        match tzid.and_then(|(_, values)| values.first()) {
+            Some(tzid) => tzid.parse().map(Zone::Named).unwrap_or(Zone::Floating),
+            None => Zone::Floating,
+        }
+    }
+
+    fn to_local(self, time: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            Zone::Floating => time,
+            Zone::Utc => shift(time, &Utc, &Local),
+            Zone::Named(tz) => shift(time, &tz, &Local),
+        }
+    }
+
+    fn to_zone(self, time: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            Zone::Floating => time,
+            Zone::Utc => shift(time, &Local, &Utc),
+            Zone::Named(tz) => shift(time, &Local, &tz),
+        }
+    }
+}
+
+/// `time` on the clock of `from`, read on the clock of `to`.
+fn shift<F: TimeZone, T: TimeZone>(time: NaiveDateTime, from: &F, to: &T) -> NaiveDateTime {
+    // A time skipped by a daylight saving change is read as the hour after
+    let zoned = from
+        .from_local_datetime(&time)
+        .earliest()
+        .or_else(|| from.from_local_datetime(&(time + Duration::hours(1))).earliest());
+    zoned.map_or(time, |zoned| zoned.with_timezone(to).naive_local())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Event {
+    uid: Option<String>,
+    start: NaiveDateTime, // in `zone`, as are the rule's times
+    zone: Zone,
+    duration: Duration,
+    rule: Option<Rule>,
+    excluded: Vec<NaiveDateTime>,         // local time
+    recurrence_id: Option<NaiveDateTime>, // local time of the occurrence this one moves
+}
+
+/// Reads the meetings at `path` that overlap `from..to`, from one `.ics` file
+/// or every `.ics` file in a directory.
+pub fn load(path: &Path, from: NaiveDateTime, to: NaiveDateTime) -> Result<Vec<Meeting>, String> {
+    let files = if path.is_dir() {
+        let entries = std::fs::read_dir(path)
+            .map_err(|e| format!("Failed to read calendar directory: {}", e))?;
+        entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|file| file.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ics")))
+            .collect()
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let mut events = Vec::new();
+    for file in files {
+        let reader = std::fs::File::open(&file)
+            .map(BufReader::new)
+            .map_err(|e| format!("Failed to read calendar {}: {}", file.display(), e))?;
+        for calendar in ical::IcalParser::new(reader) {
+            let calendar =
+                calendar.map_err(|e| format!("Invalid calendar {}: {}", file.display(), e))?;
+            events.extend(calendar.events.iter().filter_map(parse_event));
+        }
+    }
+    Ok(expand(&events, from, to))
+}
+
+fn expand(events: &[Event], from: NaiveDateTime, to: NaiveDateTime) -> Vec<Meeting> {
+    // Moved occurrences replace the ones they name
+    let mut moved: HashMap<&str, Vec<NaiveDateTime>> = HashMap::new();
+    for event in events {
+        if let (Some(uid), Some(id)) = (&event.uid, event.recurrence_id) {
+            moved.entry(uid.as_str()).or_default().push(id);
+        }
+    }
+
+    let mut meetings = Vec::new();
+    for event in events {
+        let moved = match (&event.uid, event.recurrence_id) {
+            (Some(uid), None) => moved.get(uid.as_str()).map(Vec::as_slice).unwrap_or_default(),
+            _ => &[],
+        };
+        let starts = match &event.rule {
+            Some(rule) if event.recurrence_id.is_none() => {
+                occurrences(event.start, rule, event.zone.to_zone(to))
+            }
+            _ => vec![event.start],
+        };
+        meetings.extend(
+            starts
+                .into_iter()
+                .map(|start| event.zone.to_local(start))
+                .filter(|start| !event.excluded.contains(start) && !moved.contains(start))
+                .map(|start| Meeting { start, end: start + event.duration })
+                .filter(|meeting| meeting.end > from && meeting.start < to),
+        );
+    }
+    meetings.sort_by_key(|meeting| meeting.start);
+    meetings
+}
+
+/// Starts of the occurrences of `rule` from `start` until `to`.
+fn occurrences(start: NaiveDateTime, rule: &Rule, to: NaiveDateTime) -> Vec<NaiveDateTime> {
+    let interval = rule.interval.max(1);
+    let mut starts = Vec::new();
+    let mut period = 0u32;
+
+    loop {
+        let candidates = match rule.frequency {
+            Frequency::Daily => vec![start + Duration::days(i64::from(period * interval))],
+            Frequency::Weekly if rule.by_day.is_empty() => {
+                vec![start + Duration::weeks(i64::from(period * interval))]
+            }
+            Frequency::Weekly => {
+                let week = start.date()
+                    - Duration::days(start.weekday().num_days_from_monday().into())
+                    + Duration::weeks(i64::from(period * interval));
+                let mut days: Vec<_> = rule
+                    .by_day
+                    .iter()
+                    .map(|day| {
+                        (week + Duration::days(day.num_days_from_monday().into()))
+                            .and_time(start.time())
+                    })
+                    .filter(|&candidate| candidate >= start)
+                    .collect();
+                days.sort();
+                days
+            }
+            Frequency::Monthly => {
+                start.checked_add_months(Months::new(period * interval)).into_iter().collect()
+            }
+            Frequency::Yearly => {
+                start.checked_add_months(Months::new(period * interval * 12)).into_iter().collect()
+            }
+        };
+        period += 1;
+
+        for candidate in candidates {
+            if candidate > to || rule.until.is_some_and(|until| candidate > until) {
+                return starts;
+            }
+            // Months without the start's day are skipped, not clamped to their end
+            if matches!(rule.frequency, Frequency::Monthly | Frequency::Yearly)
+                && candidate.day() != start.day()
+            {
+                continue;
+            }
+            starts.push(candidate);
+            if rule.count.is_some_and(|count| starts.len() >= count as usize) {
+                return starts;
+            }
+        }
+    }
+}
+
+fn parse_event(event: &IcalEvent) -> Option<Event> {
+    let property = |name: &str| event.properties.iter().find(|property| property.name == name);
+    let value = |name: &str| property(name).and_then(|property| property.value.as_deref());
+    // Local time, for comparing times written in different zones
+    let local_time = |property: &Property| {
+        let zone = Zone::of(property);
+        parse_time(property.value.as_deref()?).map(|time| zone.to_local(time))
+    };
+
+    if value("TRANSP") == Some("TRANSPARENT") || value("STATUS") == Some("CANCELLED") {
+        return None;
+    }
+    let zone = Zone::of(property("DTSTART")?);
+    let start = parse_time(value("DTSTART")?)?;
+    let duration = match (property("DTEND").and_then(local_time), value("DURATION")) {
+        (Some(end), _) => end - zone.to_local(start),
+        (None, Some(duration)) => parse_duration(duration)?,
+        (None, None) => return None,
+    };
+    if duration <= Duration::zero() {
+        return None;
+    }
+
+    let excluded = event
+        .properties
+        .iter()
+        .filter(|property| property.name == "EXDATE")
+        .flat_map(|property| {
+            let zone = Zone::of(property);
+            let values = property.value.as_deref().unwrap_or_default().split(',');
+            values.filter_map(move |value| parse_time(value).map(|time| zone.to_local(time)))
+        })
+        .collect();
+
+    Some(Event {
+        uid: value("UID").map(str::to_string),
+        start,
+        zone,
+        duration,
+        rule: value("RRULE").and_then(|rule| parse_rule(rule, zone)),
+        excluded,
+        recurrence_id: property("RECURRENCE-ID").and_then(local_time),
+    })
+}
+
+/// A date-time on the clock of the zone it is written in, with any `Z` for
+/// UTC left to `Zone::of`. Dates alone, from all-day events, are not times and
+/// give `None`.
+fn parse_time(value: &str) -> Option<NaiveDateTime> {
+    let value = value.strip_suffix('Z').unwrap_or(value);
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()
+}
+
+/// The rule's end on the clock of `zone`, the zone of the event's start.
+fn parse_rule_until(value: &str, zone: Zone) -> Option<NaiveDateTime> {
+    match parse_time(value) {
+        Some(time) if value.ends_with('Z') => Some(zone.to_zone(Zone::Utc.to_local(time))),
+        Some(time) => Some(time),
+        None => {
+            let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+            Some(date.and_time(NaiveTime::MIN) + Duration::days(1) - Duration::seconds(1))
+        }
+    }
+}
+
+fn parse_rule(value: &str, zone: Zone) -> Option<Rule> {
+    let mut rule =
+        Rule { frequency: Frequency::Daily, interval: 1, count: None, until: None, by_day: vec![] };
+    let mut frequency = None;
+    for part in value.split(';') {
+        let (key, value) = part.split_once('=')?;
+        match key {
+            "FREQ" => {
+                frequency = Some(match value {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    _ => return None,
+                })
+            }
+            "INTERVAL" => rule.interval = value.parse().ok()?,
+            "COUNT" => rule.count = Some(value.parse().ok()?),
+            "UNTIL" => rule.until = Some(parse_rule_until(value, zone)?),
+            "BYDAY" => {
+                rule.by_day = value.split(',').map(parse_weekday).collect::<Option<_>>()?;
+            }
+            "WKST" => {}
+            _ => return None, // a rule this cannot expand faithfully
+        }
+    }
+    rule.frequency = frequency?;
+    if !rule.by_day.is_empty() && rule.frequency != Frequency::Weekly {
+        return None;
+    }
+    Some(rule)
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    Some(match value {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+/// An iCalendar duration such as `PT1H30M` or `P1D`.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.strip_prefix('+').unwrap_or(value).strip_prefix('P')?;
+    let (days, time) = value.split_once('T').unwrap_or((value, ""));
+
+    let mut total = Duration::zero();
+    for (part, units) in [
+        (days, &[('W', 604_800), ('D', 86_400)][..]),
+        (time, &[('H', 3600), ('M', 60), ('S', 1)][..]),
+    ] {
+        let mut rest = part;
+        for &(unit, seconds) in units {
+            if let Some((number, tail)) = rest.split_once(unit) {
+                total += Duration::seconds(number.parse::<i64>().ok()? * seconds);
+                rest = tail;
+            }
+        }
+        if !rest.is_empty() {
+            return None;
+        }
+    }
+    Some(total)
+}
+
+/// Keeps the meetings of the configured calendar at hand, reading it again
+/// when the path changes and every few minutes.
+#[derive(Debug, Default)]
+pub struct MeetingWatcher {
+    path: Option<PathBuf>,
+    loaded_at: Option<NaiveDateTime>,
+    meetings: Vec<Meeting>,
+    // Read in progress, answered from `meetings` until it finishes
+    pending: Option<oneshot::Receiver<Vec<Meeting>>>,
+}
+
+impl MeetingWatcher {
+    /// Whether a meeting from the calendar at `path` is on at `now`. The
+    /// calendar is read on the blocking thread pool, so this never waits for
+    /// it. Must be called from within the Tokio runtime.
+    pub fn in_meeting(&mut self, path: Option<&str>, now: NaiveDateTime) -> bool {
+        let path = path.filter(|path| !path.trim().is_empty()).map(PathBuf::from);
+        if path != self.path {
+            // Meetings of another calendar, loaded or on their way, do not count
+            self.path = path;
+            self.loaded_at = None;
+            self.meetings.clear();
+            self.pending = None;
+        }
+
+        if let Some(pending) = &mut self.pending {
+            match pending.try_recv() {
+                Ok(meetings) => {
+                    self.meetings = meetings;
+                    self.pending = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => self.pending = None,
+            }
+        }
+
+        let stale = self.loaded_at.is_none_or(|loaded| {
+            now - loaded >= Duration::seconds(RELOAD_INTERVAL_SECS) || now < loaded
+        });
+        if let Some(path) = self.path.clone().filter(|_| stale && self.pending.is_none()) {
+            self.loaded_at = Some(now);
+            let (sender, receiver) = oneshot::channel();
+            let to = now + Duration::seconds(HORIZON_SECS);
+            tokio::task::spawn_blocking(move || {
+                let meetings = load(&path, now, to).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    Vec::new()
+                });
+                let _ = sender.send(meetings);
+            });
+            self.pending = Some(receiver);
+        }
+
+        self.meetings.iter().any(|meeting| meeting.contains(now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> NaiveDateTime {
+        parse_time(value).unwrap()
+    }
+
+    fn madrid(value: &str) -> NaiveDateTime {
+        Zone::Named(Tz::Europe__Madrid).to_local(time(value))
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("rsi-assistant-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    const STANDUP: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:standup\r
+DTSTART;TZID=Europe/Madrid:20240101T093000\r
+DURATION:PT15M\r
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR;COUNT=6\r
+EXDATE;TZID=Europe/Madrid:20240103T093000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:standup\r
+RECURRENCE-ID;TZID=Europe/Madrid:20240105T093000\r
+DTSTART;TZID=Europe/Madrid:20240105T110000\r
+DTEND;TZID=Europe/Madrid:20240105T111500\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:lunch\r
+DTSTART:20240102T130000\r
+DTEND:20240102T140000\r
+TRANSP:TRANSPARENT\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:holiday\r
+DTSTART;VALUE=DATE:20240104\r
+DTEND;VALUE=DATE:20240105\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_recurring_events_expanded() {
+        let dir = temp_dir("calendar");
+        let file = dir.join("work.ics");
+        std::fs::write(&file, STANDUP).unwrap();
+
+        let meetings = load(&file, time("20240101T000000"), time("20240201T000000")).unwrap();
+        let starts: Vec<_> = meetings.iter().map(|meeting| meeting.start).collect();
+        assert_eq!(
+            starts,
+            [
+                madrid("20240101T093000"),
+                madrid("20240105T110000"), // moved
+                madrid("20240108T093000"),
+                madrid("20240110T093000"),
+                madrid("20240112T093000"),
+            ]
+        );
+        assert_eq!(meetings[0].end, madrid("20240101T094500"));
+
+        // Reading the directory finds the same file
+        assert_eq!(load(&dir, time("20240101T000000"), time("20240201T000000")).unwrap(), meetings);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rules() {
+        let start = time("20240131T100000");
+        let rule = parse_rule("FREQ=MONTHLY;UNTIL=20240601", Zone::Floating).unwrap();
+        assert_eq!(
+            occurrences(start, &rule, time("20250101T000000")),
+            [start, time("20240331T100000"), time("20240531T100000")]
+        );
+
+        let rule = parse_rule("FREQ=DAILY;INTERVAL=2", Zone::Floating).unwrap();
+        assert_eq!(occurrences(start, &rule, time("20240204T100000")).len(), 3);
+
+        assert_eq!(parse_rule("FREQ=MONTHLY;BYDAY=2TU", Zone::Floating), None);
+        assert_eq!(parse_duration("PT1H30M"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("P1DT1S"), Some(Duration::seconds(86_401)));
+        assert_eq!(parse_duration("PT1X"), None);
+    }
+
+    #[test]
+    fn test_times_follow_their_zone() {
+        let dir = temp_dir("zones");
+        let file = dir.join("calls.ics");
+        std::fs::write(
+            &file,
+            "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:call\r
+DTSTART;TZID=America/New_York:20240304T090000\r
+DTEND;TZID=America/New_York:20240304T093000\r
+RRULE:FREQ=WEEKLY;UNTIL=20240311T130000Z\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:review\r
+DTSTART:20240305T150000Z\r
+DURATION:PT1H\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:outlook\r
+DTSTART;TZID=W. Europe Standard Time:20240306T100000\r
+DURATION:PT1H\r
+END:VEVENT\r
+END:VCALENDAR\r
+",
+        )
+        .unwrap();
+
+        let utc = |value| Zone::Utc.to_local(time(value));
+        let meetings = load(&file, time("20240301T000000"), time("20240401T000000")).unwrap();
+        let starts: Vec<_> = meetings.iter().map(|meeting| meeting.start).collect();
+        // 09:00 in New York is 14:00 UTC until daylight saving time starts on
+        // March 10, and 13:00 UTC after. A zone not in the tz database is local.
+        assert_eq!(
+            starts,
+            [
+                utc("20240304T140000"),
+                utc("20240305T150000"),
+                time("20240306T100000"),
+                utc("20240311T130000"),
+            ]
+        );
+        assert_eq!(meetings[0].end, utc("20240304T143000"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    async fn loaded(watcher: &mut MeetingWatcher, path: Option<&str>, now: NaiveDateTime) {
+        while watcher.pending.is_some() {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            watcher.in_meeting(path, now);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watcher_follows_path() {
+        let dir = temp_dir("meetings");
+        std::fs::write(dir.join("work.ics"), STANDUP).unwrap();
+        let path = dir.to_str();
+
+        let mut watcher = MeetingWatcher::default();
+        let standup = madrid("20240101T093500");
+        // Nothing is known until the first read finishes
+        assert!(!watcher.in_meeting(path, standup));
+        loaded(&mut watcher, path, standup).await;
+        assert!(watcher.in_meeting(path, standup));
+        assert!(!watcher.in_meeting(path, madrid("20240101T094500")));
+
+        // A reload answers from the meetings read before until it finishes
+        watcher.loaded_at = Some(standup - Duration::seconds(RELOAD_INTERVAL_SECS));
+        assert!(watcher.in_meeting(path, standup));
+        assert!(watcher.pending.is_some());
+        loaded(&mut watcher, path, standup).await;
+
+        assert!(!watcher.in_meeting(None, madrid("20240108T093500")));
+        assert!(watcher.meetings.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,433 @@
+//! Command line interface. A launch with a command is sent over the API socket
+//! when the app or the daemon serves it. Otherwise it is forwarded to the
+//! running app, which carries it out and replies over a Unix socket.
+
+use crate::statusbar::StatusFormat;
+use crate::timer::{BreakType, OperationMode, TimerStatus};
+use std::path::{Path, PathBuf};
+
+pub const USAGE: &str = "\
+Usage: rsi-assistant [COMMAND]
+
+Commands:
+  break <micro|rest>               Start a break now
+  mode <normal|quiet|suspended>    Switch operation mode
+  status [OPTIONS]                 Show the timer status
+
+Status options:
+  --json                           Print the status as JSON
+  --format <FORMAT>                waybar, i3blocks, json, or a template such
+                                   as '{micro} / {rest}'
+  --follow                         Print a line each time the output changes
+                                   (needs the API socket)
+
+Without a command the app is started, or brought forward if already running.";
+
+/// Reply to a command the daemon holds the lock for, when it does not serve the
+/// API socket.
+pub const DAEMON_WITHOUT_API: &str =
+    "The daemon is running; turn on apiSocket in the settings to send it commands";
+
+/// A command, with arguments in the form the matching Tauri command takes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    Break { break_type: String }, // for trigger_break
+    Mode { mode: String },        // for set_mode
+    Status { format: Option<StatusFormat>, follow: bool },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliRequest {
+    pub command: CliCommand,
+    // Socket the launching process waits on for the result
+    pub reply: Option<PathBuf>,
+}
+
+/// Parses the process arguments, including the binary name. Returns `None` for
+/// a plain launch.
+pub fn parse_args(args: &[String]) -> Result<Option<CliRequest>, String> {
+    let mut args: Vec<&str> = args.iter().skip(1).map(String::as_str).collect();
+
+    let mut reply = None;
+    if let Some(index) = args.iter().position(|&arg| arg == "--reply") {
+        let path = args.get(index + 1).ok_or("Missing path for --reply")?;
+        reply = Some(PathBuf::from(path));
+        args.drain(index..index + 2);
+    }
+
+    let command = match args.as_slice() {
+        [] => return Ok(None),
+        ["break", break_type] => {
+            break_type.parse::<BreakType>()?;
+            CliCommand::Break { break_type: break_type.to_string() }
+        }
+        ["mode", mode] => {
+            let mode: OperationMode = mode.parse()?;
+            CliCommand::Mode { mode: format!("{:?}", mode) }
+        }
+        ["status", options @ ..] => parse_status_options(options)?,
+        _ => return Err(format!("Unknown command: {}", args.join(" "))),
+    };
+    Ok(Some(CliRequest { command, reply }))
+}
+
+fn parse_status_options(options: &[&str]) -> Result<CliCommand, String> {
+    let mut format = None;
+    let mut follow = false;
+    let mut options = options.iter();
+    while let Some(&option) = options.next() {
+        match option {
+            "--json" => format = Some(StatusFormat::Json),
+            "--format" => {
+                format = Some(options.next().ok_or("Missing value for --format")?.parse()?)
+            }
+            "--follow" => follow = true,
+            _ => return Err(format!("Unknown option: {}", option)),
+        }
+    }
+    Ok(CliCommand::Status { format, follow })
+}
+
+pub fn format_status(status: &TimerStatus, format: Option<&StatusFormat>) -> String {
+    match format {
+        Some(format) => crate::statusbar::render(status, format),
+        None => format!("Mode: {:?}\n{}", status.mode, crate::tray::tooltip(status)),
+    }
+}
+
+/// Sends a command's result to the launch waiting on `path`.
+#[cfg(unix)]
+pub fn send_reply(path: &Path, result: &Result<String, String>) {
+    use std::io::Write;
+
+    let sent = std::os::unix::net::UnixStream::connect(path).and_then(|mut stream| {
+        stream.write_all(serde_json::to_string(result).unwrap_or_default().as_bytes())
+    });
+    if let Err(e) = sent {
+        eprintln!("Failed to reply to CLI command: {}", e);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn send_reply(_path: &Path, _result: &Result<String, String>) {}
+
+/// Handles a launch with a command: relaunches the binary with `--reply`, so
+/// that the single-instance plugin forwards the command, and prints the reply.
+///
+/// Returns the exit code, or `None` if the app should start normally.
+pub fn forward_from_args() -> Option<i32> {
+    let args: Vec<String> = std::env::args().collect();
+    match parse_args(&args) {
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            Some(2)
+        }
+        #[cfg(unix)]
+        Ok(Some(CliRequest { command: CliCommand::Status { format, follow: true }, .. })) => {
+            Some(follow(format))
+        }
+        #[cfg(not(unix))]
+        Ok(Some(CliRequest { command: CliCommand::Status { follow: true, .. }, .. })) => {
+            eprintln!("--follow is not supported on this platform");
+            Some(1)
+        }
+        #[cfg(unix)]
+        Ok(Some(CliRequest { command, reply: None })) => Some(forward(&command, &args[1..])),
+        _ => None,
+    }
+}
+
+#[cfg(unix)]
+fn forward(command: &CliCommand, args: &[String]) -> i32 {
+    use std::io::Read;
+    use std::os::unix::net::UnixListener;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    // The API socket reaches the daemon too, which the relaunch cannot
+    if let Some(result) = call_api(&crate::rpc::socket_path(), command) {
+        return print_result(result);
+    }
+
+    // Kept where only the user can reach it, so no one else can bind it first
+    // or send a forged reply
+    let dir = crate::rpc::runtime_dir();
+    if let Err(e) = crate::rpc::private_dir(&dir) {
+        eprintln!("Failed to open reply socket: {}", e);
+        return 1;
+    }
+    let path = dir.join(format!("cli-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = match UnixListener::bind(&path) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Failed to open reply socket: {}", e);
+            return 1;
+        }
+    };
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        if let Ok((mut stream, _)) = listener.accept() {
+            let mut reply = String::new();
+            let _ = stream.read_to_string(&mut reply);
+            let _ = tx.send(reply);
+        }
+    });
+
+    let child = std::env::current_exe().and_then(|exe| {
+        std::process::Command::new(exe).args(args).arg("--reply").arg(&path).spawn()
+    });
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("Failed to launch RSI Assistant: {}", e);
+            return 1;
+        }
+    };
+
+    // The relaunch exits once the running instance has the command, or keeps
+    // running as the app itself if there was none
+    let code = loop {
+        if let Ok(reply) = rx.recv_timeout(Duration::from_millis(100)) {
+            break match serde_json::from_str::<Result<String, String>>(&reply) {
+                Ok(result) => print_result(result),
+                Err(_) => {
+                    eprintln!("Invalid reply from RSI Assistant");
+                    1
+                }
+            };
+        }
+        if let Ok(Some(status)) = child.try_wait() {
+            break status.code().unwrap_or(1);
+        }
+    };
+    let _ = std::fs::remove_file(&path);
+    code
+}
+
+/// Carries out the command over the API socket at `path`. Returns `None` if
+/// nothing serves it.
+#[cfg(unix)]
+fn call_api(path: &Path, command: &CliCommand) -> Option<Result<String, String>> {
+    use std::io::{BufRead, BufReader, Write};
+
+    let mut stream = std::os::unix::net::UnixStream::connect(path).ok()?;
+    let (method, params) = match command {
+        CliCommand::Break { break_type } => {
+            ("trigger", serde_json::json!({ "breakType": break_type }))
+        }
+        CliCommand::Mode { mode } => {
+            let mode: OperationMode = mode.parse().ok()?;
+            ("setMode", serde_json::json!({ "mode": mode }))
+        }
+        CliCommand::Status { format: Some(format), .. } => {
+            ("status", serde_json::json!({ "format": format.to_string() }))
+        }
+        CliCommand::Status { format: None, .. } => ("status", serde_json::Value::Null),
+    };
+    let request =
+        serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    // Once sent, the command may have been carried out, so errors are reported
+    // rather than left to the relaunch
+    if let Err(e) = writeln!(stream, "{}", request) {
+        return Some(Err(format!("Failed to send command: {}", e)));
+    }
+    let mut line = String::new();
+    if let Err(e) = BufReader::new(stream).read_line(&mut line) {
+        return Some(Err(format!("Failed to read reply: {}", e)));
+    }
+    let Ok(reply) = serde_json::from_str::<serde_json::Value>(&line) else {
+        return Some(Err("Invalid reply from RSI Assistant".to_string()));
+    };
+    if let Some(error) = reply["error"]["message"].as_str() {
+        return Some(Err(error.to_string()));
+    }
+    Some(match (command, &reply["result"]) {
+        (CliCommand::Status { format: None, .. }, status) => serde_json::from_value(status.clone())
+            .map(|status| format_status(&status, None))
+            .map_err(|e| format!("Invalid reply from RSI Assistant: {}", e)),
+        (CliCommand::Status { .. }, serde_json::Value::String(output)) => Ok(output.clone()),
+        _ => Ok(String::new()),
+    })
+}
+
+/// Prints a command's result and returns the exit code.
+#[cfg(unix)]
+fn print_result(result: Result<String, String>) -> i32 {
+    match result {
+        Ok(output) => {
+            if !output.is_empty() {
+                println!("{}", output);
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+/// Subscribes over the API socket and prints the status in `format` whenever
+/// it changes, until the app exits.
+#[cfg(unix)]
+fn follow(format: Option<StatusFormat>) -> i32 {
+    use std::io::{BufRead, BufReader, Write};
+
+    let mut stream = match std::os::unix::net::UnixStream::connect(crate::rpc::socket_path()) {
+        Ok(stream) => stream,
+        Err(_) => {
+            eprintln!(
+                "RSI Assistant is not serving the API socket; turn on apiSocket in the settings"
+            );
+            return 1;
+        }
+    };
+    let format = format.unwrap_or(StatusFormat::Json);
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "subscribe",
+        "params": { "format": format.to_string() },
+    });
+    if let Err(e) = writeln!(stream, "{}", request) {
+        eprintln!("Failed to subscribe: {}", e);
+        return 1;
+    }
+
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else { break };
+        let message: serde_json::Value = serde_json::from_str(&line).unwrap_or_default();
+        if let Some(error) = message["error"]["message"].as_str() {
+            eprintln!("{}", error);
+            return 1;
+        }
+        let output = message["result"].as_str().or(message["params"]["line"].as_str());
+        if let Some(output) = output {
+            println!("{}", output);
+        }
+    }
+    eprintln!("RSI Assistant stopped");
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        std::iter::once("rsi-assistant").chain(line.split_whitespace()).map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse_args(&args("")), Ok(None));
+
+        let request = parse_args(&args("break rest")).unwrap().unwrap();
+        assert_eq!(request.command, CliCommand::Break { break_type: "rest".to_string() });
+        assert_eq!(request.reply, None);
+
+        let request = parse_args(&args("mode quiet --reply /tmp/r.sock")).unwrap().unwrap();
+        assert_eq!(request.command, CliCommand::Mode { mode: "Quiet".to_string() });
+        assert_eq!(request.reply, Some(PathBuf::from("/tmp/r.sock")));
+
+        let request = parse_args(&args("status --json")).unwrap().unwrap();
+        assert_eq!(
+            request.command,
+            CliCommand::Status { format: Some(StatusFormat::Json), follow: false }
+        );
+
+        let request = parse_args(&args("status --follow --format waybar")).unwrap().unwrap();
+        assert_eq!(
+            request.command,
+            CliCommand::Status { format: Some(StatusFormat::Waybar), follow: true }
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_commands() {
+        assert_eq!(parse_args(&args("break long")), Err("Invalid break type".to_string()));
+        assert_eq!(parse_args(&args("mode loud")), Err("Invalid mode: loud".to_string()));
+        assert!(parse_args(&args("status --yaml")).is_err());
+        assert_eq!(
+            parse_args(&args("status --format yaml")),
+            Err("Unknown status format: yaml".to_string())
+        );
+        assert!(parse_args(&args("status --reply")).is_err());
+    }
+
+    #[test]
+    fn test_format_status() {
+        let service = crate::timer::TimerService::new(crate::timer::BreakConfig::default());
+        let status = service.get_status();
+
+        assert_eq!(format_status(&status, None), "Mode: Normal\nMicrobreak in 3:00, rest in 45:00");
+        let json: serde_json::Value =
+            serde_json::from_str(&format_status(&status, Some(&StatusFormat::Json))).unwrap();
+        assert_eq!(json["microTarget"], 180);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reply_round_trip() {
+        use std::io::Read;
+
+        let path =
+            std::env::temp_dir().join(format!("rsi-assistant-test-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+
+        send_reply(&path, &Err("RSI Assistant is not running".to_string()));
+        let mut reply = String::new();
+        listener.accept().unwrap().0.read_to_string(&mut reply).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            serde_json::from_str::<Result<String, String>>(&reply).unwrap(),
+            Err("RSI Assistant is not running".to_string())
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_commands_over_api_socket() {
+        use crate::commands::AppState;
+        use crate::timer::{BreakConfig, TimerService};
+        use std::sync::{Arc, Mutex};
+
+        let dir = std::env::temp_dir().join(format!("rsi-assistant-cli-{}", std::process::id()));
+        let path = dir.join("api.sock");
+        let state = Arc::new(AppState {
+            timer_service: Mutex::new(TimerService::new(BreakConfig::default())),
+            stats_store: Mutex::new(Default::default()),
+        });
+        let mut api = crate::rpc::RpcApi::new(path.clone());
+        let status = state.timer_service.lock().unwrap().get_status();
+        api.update(true, &state, &status);
+
+        let call = |line: &'static str| {
+            let path = path.clone();
+            let command = parse_args(&args(line)).unwrap().unwrap().command;
+            tokio::task::spawn_blocking(move || call_api(&path, &command))
+        };
+        assert_eq!(call("mode quiet").await.unwrap(), Some(Ok(String::new())));
+        assert_eq!(state.timer_service.lock().unwrap().config.mode, OperationMode::Quiet);
+        assert_eq!(
+            call("status").await.unwrap(),
+            Some(Ok("Mode: Quiet\nMicrobreak in 3:00, rest in 45:00".to_string()))
+        );
+        assert_eq!(call("status --format {rest}").await.unwrap(), Some(Ok("45:00".to_string())));
+        assert_eq!(call("break rest").await.unwrap(), Some(Ok(String::new())));
+        {
+            let mut service = state.timer_service.lock().unwrap();
+            service.tick(false);
+            assert!(service.active_break.is_some());
+        }
+
+        // Nothing serving the socket leaves the command to the relaunch
+        api.update(false, &state, &status);
+        assert_eq!(call("status").await.unwrap(), None);
+        std::fs::remove_dir(&dir).unwrap();
+    }
+}
@@ -1,7 +1,14 @@
-use crate::stats::{DailyStats, StatsStore};
-use crate::timer::{BreakConfig, TimerService, TimerStatus};
-use std::sync::Mutex;
-use tauri::State;
+use crate::messages::Prompt;
+use crate::stats::{BreakOverride, DailyStats, StatsStore};
+use crate::timer::{BreakAction, BreakConfig, BreakType, TimerEvent, TimerService, TimerStatus};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+// Seconds without input before the user counts as idle
+const IDLE_THRESHOLD_SECS: u64 = 5;
+// During a break any input in the last second counts as activity
+const BREAK_IDLE_THRESHOLD_SECS: u64 = 0;
 
 // AppState definition
 pub struct AppState {
@@ -9,6 +16,104 @@ pub struct AppState {
     pub stats_store: Mutex<StatsStore>,
 }
 
+/// Gives services running outside of Tauri commands access to the state,
+/// however the host holds it.
+pub trait StateProvider: Clone + Send + Sync + 'static {
+    fn with_state<R>(&self, f: impl FnOnce(&AppState) -> R) -> R;
+}
+
+impl StateProvider for Arc<AppState> {
+    fn with_state<R>(&self, f: impl FnOnce(&AppState) -> R) -> R {
+        f(self)
+    }
+}
+
+/// Result of one second of the background loop.
+pub struct Tick {
+    pub status: TimerStatus,
+    pub events: Vec<TimerEvent>,
+    // Notifications to show, with the actions they offer
+    pub prompts: Vec<(Prompt, Vec<BreakAction>)>,
+}
+
+impl AppState {
+    /// Advances the timer by one second and records what happened in today's
+    /// statistics.
+    pub fn tick(&self, idle_seconds: u64) -> Tick {
+        let mut service = self.timer_service.lock().unwrap();
+
+        let threshold = if service.active_break.is_some() {
+            BREAK_IDLE_THRESHOLD_SECS
+        } else {
+            IDLE_THRESHOLD_SECS
+        };
+        let events = service.tick(idle_seconds > threshold);
+        let status = service.get_status();
+
+        // Update statistics with current usage
+        let mut stats = self.stats_store.lock().unwrap();
+        let today = stats.get_or_create_today();
+        today.total_usage_seconds = status.daily_usage;
+
+        for event in &events {
+            today.record_event(*event);
+        }
+
+        // Prompt when a break starts, and again with rising urgency while it is
+        // ignored
+        let prompts = events
+            .iter()
+            .filter_map(|event| match *event {
+                TimerEvent::BreakStarted(break_type) => Some((break_type, 0)),
+                TimerEvent::BreakReprompted { break_type, repeat } => Some((break_type, repeat)),
+                _ => None,
+            })
+            .map(|(break_type, repeat)| {
+                (
+                    Prompt::from_service(&service, break_type, repeat),
+                    service.available_actions(break_type),
+                )
+            })
+            .collect();
+
+        Tick { status, events, prompts }
+    }
+
+    /// Writes statistics to the data directory shared by the app and the daemon.
+    pub fn save_stats(&self, dir: &Path) -> Result<(), String> {
+        crate::persistence::save_stats(dir, &self.stats_store.lock().unwrap())
+    }
+
+    /// Applies a break action chosen from the overlay, a notification, the tray, a
+    /// global shortcut or the API socket.
+    pub fn apply_break_action(
+        &self,
+        break_type: BreakType,
+        action: BreakAction,
+    ) -> Result<(), String> {
+        {
+            let mut service = self.timer_service.lock().unwrap();
+            match action {
+                BreakAction::TakeNow => service.take_break_now(break_type),
+                BreakAction::Postpone => service.postpone_break(break_type)?,
+                BreakAction::Skip => service.skip_break(break_type)?,
+            }
+        }
+
+        let mut store = self.stats_store.lock().unwrap();
+        let today = store.get_or_create_today();
+        match (action, break_type) {
+            (BreakAction::TakeNow, _) => {}
+            (BreakAction::Postpone, BreakType::Micro) => today.micro_postponed += 1,
+            (BreakAction::Postpone, BreakType::Rest) => today.rest_postponed += 1,
+            (BreakAction::Skip, BreakType::Micro) => today.micro_skipped += 1,
+            (BreakAction::Skip, BreakType::Rest) => today.rest_skipped += 1,
+        }
+
+        Ok(())
+    }
+}
+
 #[tauri::command]
 pub fn get_timer_state(state: State<AppState>) -> TimerStatus {
     let service = state.timer_service.lock().unwrap();
@@ -16,7 +121,21 @@ pub fn get_timer_state(state: State<AppState>) -> TimerStatus {
 }
 
 #[tauri::command]
-pub fn update_settings(state: State<AppState>, settings: BreakConfig) -> Result<(), String> {
+pub fn update_settings(
+    app: AppHandle,
+    state: State<AppState>,
+    settings: BreakConfig,
+) -> Result<(), String> {
+    // Registration runs on the main thread, so the timer is not held meanwhile
+    let current = state.timer_service.lock().unwrap().config.shortcuts.clone();
+    if settings.shortcuts != current {
+        if let Err(e) = crate::register_shortcuts(&app, &settings.shortcuts) {
+            // Keep the previous bindings working
+            let _ = crate::register_shortcuts(&app, &current);
+            return Err(e);
+        }
+    }
+
     let mut service = state.timer_service.lock().unwrap();
     service.update_config(settings);
     // Settings persistence is handled by the frontend interfacing with Tauri Store.
@@ -36,45 +155,70 @@ pub fn get_statistics(state: State<AppState>, days: usize) -> Vec<DailyStats> {
 }
 
 #[tauri::command]
-pub fn record_break_taken(state: State<AppState>, break_type: String) -> Result<(), String> {
+pub fn record_break_postponed(state: State<AppState>, break_type: String) -> Result<(), String> {
+    let break_type: BreakType = break_type.parse()?;
+    state.timer_service.lock().unwrap().check_postpone(break_type)?;
+
     let mut store = state.stats_store.lock().unwrap();
     let today = store.get_or_create_today();
 
-    match break_type.as_str() {
-        // TODO: Distinguish between prompted vs natural? Command arg?
-        // For now assuming prompted if calling this command.
-        "micro" => today.micro_prompted_taken += 1,
-        "rest" => today.rest_prompted_taken += 1,
-        _ => return Err("Invalid break type".to_string()),
+    match break_type {
+        BreakType::Micro => today.micro_postponed += 1,
+        BreakType::Rest => today.rest_postponed += 1,
     }
 
     Ok(())
 }
 
 #[tauri::command]
-pub fn record_break_postponed(state: State<AppState>, break_type: String) -> Result<(), String> {
-    let mut store = state.stats_store.lock().unwrap();
-    let today = store.get_or_create_today();
+pub fn reset_break(state: State<AppState>, break_type: String) -> Result<(), String> {
+    state.apply_break_action(break_type.parse()?, BreakAction::Skip)
+}
 
-    match break_type.as_str() {
-        "micro" => today.micro_postponed += 1,
-        "rest" => today.rest_postponed += 1,
-        _ => return Err("Invalid break type".to_string()),
-    }
+#[tauri::command]
+pub fn postpone_break(state: State<AppState>, break_type: String) -> Result<(), String> {
+    state.apply_break_action(break_type.parse()?, BreakAction::Postpone)
+}
 
-    Ok(())
+#[tauri::command]
+pub fn break_action(
+    state: State<AppState>,
+    break_type: String,
+    action: BreakAction,
+) -> Result<(), String> {
+    state.apply_break_action(break_type.parse()?, action)
 }
 
+/// Ends a break regardless of its strictness. The reason is kept in today's
+/// statistics.
 #[tauri::command]
-pub fn reset_break(state: State<AppState>, break_type: String) -> Result<(), String> {
-    let mut service = state.timer_service.lock().unwrap();
+pub fn override_break(
+    state: State<AppState>,
+    break_type: String,
+    reason: String,
+) -> Result<(), String> {
+    let break_type: BreakType = break_type.parse()?;
+    let reason = reason.trim().to_string();
+    if reason.is_empty() {
+        return Err("An override reason is required".to_string());
+    }
 
-    match break_type.as_str() {
-        "micro" => service.reset_microbreak(),
-        "rest" => service.reset_rest_break(),
-        _ => return Err("Invalid break type".to_string()),
+    {
+        let mut service = state.timer_service.lock().unwrap();
+        match break_type {
+            BreakType::Micro => service.reset_microbreak(),
+            BreakType::Rest => service.reset_rest_break(),
+        }
     }
 
+    let mut store = state.stats_store.lock().unwrap();
+    let today = store.get_or_create_today();
+    today.overrides.push(BreakOverride {
+        time: chrono::Local::now().format("%H:%M:%S").to_string(),
+        break_type,
+        reason,
+    });
+
     Ok(())
 }
 
@@ -82,13 +226,7 @@ pub fn reset_break(state: State<AppState>, break_type: String) -> Result<(), Str
 pub fn set_mode(state: State<AppState>, mode: String) -> Result<(), String> {
     let mut service = state.timer_service.lock().unwrap();
 
-    let operation_mode = match mode.as_str() {
-        "Normal" => crate::timer::OperationMode::Normal,
-        "Quiet" => crate::timer::OperationMode::Quiet,
-        "Suspended" => crate::timer::OperationMode::Suspended,
-        _ => return Err("Invalid mode".to_string()),
-    };
-
+    let operation_mode: crate::timer::OperationMode = mode.parse()?;
     service.set_mode(operation_mode);
     Ok(())
 }
@@ -140,6 +278,41 @@ mod tests {
         assert_eq!(stats[0].micro_prompted_taken, 1);
     }
 
+    #[test]
+    fn test_locked_break_blocks_reset() {
+        let mut config = BreakConfig::default();
+        config.rest_strictness = crate::timer::BreakStrictness::Locked;
+        let mut service = crate::timer::TimerService::new(config);
+
+        service.trigger_rest_break();
+        service.tick(false);
+        assert!(service.check_skip(BreakType::Rest).is_err());
+        assert!(service.check_postpone(BreakType::Rest).is_err());
+        // Microbreak strictness is independent
+        assert!(service.check_skip(BreakType::Micro).is_ok());
+    }
+
+    #[test]
+    fn test_apply_break_action() {
+        let mut config = BreakConfig::default();
+        config.rest_strictness = crate::timer::BreakStrictness::Postponable;
+        let state = AppState {
+            timer_service: Mutex::new(crate::timer::TimerService::new(config)),
+            stats_store: Mutex::new(StatsStore::default()),
+        };
+
+        state.apply_break_action(BreakType::Rest, BreakAction::TakeNow).unwrap();
+        assert!(state.apply_break_action(BreakType::Rest, BreakAction::Skip).is_err());
+        state.apply_break_action(BreakType::Rest, BreakAction::Postpone).unwrap();
+        state.apply_break_action(BreakType::Micro, BreakAction::Skip).unwrap();
+
+        let mut store = state.stats_store.lock().unwrap();
+        let today = store.get_or_create_today();
+        assert_eq!(today.rest_postponed, 1);
+        assert_eq!(today.rest_skipped, 0);
+        assert_eq!(today.micro_skipped, 1);
+    }
+
     #[test]
     fn test_stats_invalid_break_type() {
         // This tests the match logic for invalid break types
@@ -57,37 +57,6 @@ mod tests {
         assert_eq!(stats.len(), 0);
     }
 
-    #[test]
-    fn test_record_break_taken_micro() {
-        let state = create_test_state();
-        let result = record_break_taken(tauri::State::from(&state), "micro".to_string());
-
-        assert!(result.is_ok());
-
-        let stats = get_statistics(tauri::State::from(&state), 1);
-        assert_eq!(stats.len(), 1);
-        assert_eq!(stats[0].microbreaks_taken, 1);
-    }
-
-    #[test]
-    fn test_record_break_taken_rest() {
-        let state = create_test_state();
-        let result = record_break_taken(tauri::State::from(&state), "rest".to_string());
-
-        assert!(result.is_ok());
-
-        let stats = get_statistics(tauri::State::from(&state), 1);
-        assert_eq!(stats[0].rest_breaks_taken, 1);
-    }
-
-    #[test]
-    fn test_record_break_taken_invalid() {
-        let state = create_test_state();
-        let result = record_break_taken(tauri::State::from(&state), "invalid".to_string());
-
-        assert!(result.is_err());
-    }
-
     #[test]
     fn test_record_break_postponed_micro() {
         let state = create_test_state();
@@ -114,12 +83,12 @@ mod tests {
     fn test_multiple_breaks_same_day() {
         let state = create_test_state();
 
-        record_break_taken(tauri::State::from(&state), "micro".to_string()).unwrap();
-        record_break_taken(tauri::State::from(&state), "micro".to_string()).unwrap();
+        record_break_postponed(tauri::State::from(&state), "micro".to_string()).unwrap();
+        record_break_postponed(tauri::State::from(&state), "micro".to_string()).unwrap();
         record_break_postponed(tauri::State::from(&state), "rest".to_string()).unwrap();
 
         let stats = get_statistics(tauri::State::from(&state), 1);
-        assert_eq!(stats[0].microbreaks_taken, 2);
+        assert_eq!(stats[0].microbreaks_postponed, 2);
         assert_eq!(stats[0].rest_breaks_postponed, 1);
     }
 }
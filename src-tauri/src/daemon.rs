@@ -0,0 +1,115 @@
+//! Headless daemon: the break timer, idle detection, statistics and desktop
+//! notifications without a webview. It reads settings from and writes
+//! statistics to the same files as the app, so both share one history, and
+//! refuses to start while the app is running.
+
+use crate::commands::AppState;
+use crate::persistence::{self, SettingsWatcher};
+use crate::pipeline::{FallbackNotifier, TickPipeline};
+use crate::timer::TimerService;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// Runs until interrupted and returns the exit code.
+pub fn run() -> i32 {
+    let Some(data_dir) = persistence::data_dir() else {
+        eprintln!("Cannot locate the data directory");
+        return 1;
+    };
+    let _lock = match persistence::lock_data_dir(&data_dir) {
+        Ok(lock) => lock,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+
+    let mut settings = SettingsWatcher::new(persistence::settings_path(&data_dir));
+    let config = match settings.poll() {
+        Some(Ok(config)) => config,
+        Some(Err(e)) => {
+            eprintln!("{}, using defaults", e);
+            Default::default()
+        }
+        None => Default::default(),
+    };
+
+    // Starting empty here would overwrite the history on the first save
+    let stats_store = match persistence::load_stats(&data_dir) {
+        Ok(stats) => stats,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+
+    let state = Arc::new(AppState {
+        timer_service: Mutex::new(TimerService::new(config)),
+        stats_store: Mutex::new(stats_store),
+    });
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Failed to start runtime: {}", e);
+            return 1;
+        }
+    };
+    runtime.block_on(run_loop(state.clone(), data_dir.clone(), settings));
+
+    match state.save_stats(&data_dir) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+async fn run_loop(state: Arc<AppState>, data_dir: PathBuf, settings: SettingsWatcher) {
+    let fallback: FallbackNotifier = Arc::new(|title, body| {
+        // Waits for the notification server, so it gets a thread of its own
+        tokio::task::spawn_blocking(move || {
+            let shown = notify_rust::Notification::new()
+                .appname("RSI Assistant")
+                .summary(&title)
+                .body(&body)
+                .show();
+            if let Err(e) = shown {
+                eprintln!("Failed to show notification: {}", e);
+                eprintln!("{}: {}", title, body);
+            }
+        });
+    });
+    // Settings changed from the app's Settings page apply here too
+    let mut pipeline = TickPipeline::connect(state, data_dir, Some(settings), fallback).await;
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            _ = sleep(Duration::from_secs(1)) => {}
+            _ = &mut shutdown => break,
+        }
+        pipeline.tick().await;
+    }
+}
+
+/// Completes on Ctrl+C, or on SIGTERM from a service manager.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
+            return;
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
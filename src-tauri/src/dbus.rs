@@ -0,0 +1,309 @@
+//! `org.rsiassistant.Timer` on the session bus: methods mirroring the Tauri
+//! commands, and signals when a break starts or ends and when the mode changes.
+
+use crate::commands::StateProvider;
+use crate::timer::{BreakType, OperationMode, TimerEvent, TimerStatus};
+use zbus::fdo;
+use zbus::object_server::SignalEmitter;
+use zbus::Connection;
+
+pub const BUS_NAME: &str = "org.rsiassistant.Timer";
+pub const OBJECT_PATH: &str = "/org/rsiassistant/Timer";
+
+struct TimerInterface<P> {
+    provider: P,
+}
+
+#[zbus::interface(name = "org.rsiassistant.Timer")]
+impl<P: StateProvider> TimerInterface<P> {
+    /// Starts a "micro" or "rest" break now.
+    fn trigger_break(&self, break_type: &str) -> fdo::Result<()> {
+        let break_type: BreakType = break_type.parse().map_err(fdo::Error::InvalidArgs)?;
+        self.provider.with_state(|state| {
+            let mut service = state.timer_service.lock().unwrap();
+            match break_type {
+                BreakType::Micro => service.trigger_microbreak(),
+                BreakType::Rest => service.trigger_rest_break(),
+            }
+        });
+        Ok(())
+    }
+
+    /// Switches to "normal", "quiet" or "suspended".
+    fn set_mode(&self, mode: &str) -> fdo::Result<()> {
+        let mode: OperationMode = mode.parse().map_err(fdo::Error::InvalidArgs)?;
+        self.provider.with_state(|state| state.timer_service.lock().unwrap().set_mode(mode));
+        Ok(())
+    }
+
+    /// The timer status as JSON, as from `rsi-assistant status --json`.
+    fn get_status(&self) -> String {
+        let status =
+            self.provider.with_state(|state| state.timer_service.lock().unwrap().get_status());
+        serde_json::to_string(&status).unwrap_or_default()
+    }
+
+    #[zbus(signal)]
+    async fn break_started(
+        emitter: &SignalEmitter<'_>,
+        break_type: &str,
+        duration: u64,
+    ) -> zbus::Result<()>;
+
+    /// `completed` is false when the break was skipped, postponed or reset.
+    #[zbus(signal)]
+    async fn break_ended(
+        emitter: &SignalEmitter<'_>,
+        break_type: &str,
+        completed: bool,
+    ) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn mode_changed(emitter: &SignalEmitter<'_>, mode: &str) -> zbus::Result<()>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Signal {
+    BreakStarted { break_type: BreakType, duration: u64 },
+    BreakEnded { break_type: BreakType, completed: bool },
+    ModeChanged(OperationMode),
+}
+
+/// Serves the interface and emits its signals as the timer ticks.
+pub struct DbusService<P> {
+    connection: Connection,
+    previous: Option<TimerStatus>,
+    _provider: std::marker::PhantomData<P>,
+}
+
+impl<P: StateProvider> DbusService<P> {
+    pub async fn connect(provider: P) -> zbus::Result<Self> {
+        Self::with_connection(Connection::session().await?, provider).await
+    }
+
+    pub async fn with_connection(connection: Connection, provider: P) -> zbus::Result<Self> {
+        connection.object_server().at(OBJECT_PATH, TimerInterface { provider }).await?;
+        // Fails while another instance, the app or the daemon, holds the name
+        let reply = connection
+            .request_name_with_flags(BUS_NAME, fdo::RequestNameFlags::DoNotQueue.into())
+            .await?;
+        if reply == fdo::RequestNameReply::Exists {
+            return Err(zbus::Error::NameTaken);
+        }
+        Ok(Self { connection, previous: None, _provider: std::marker::PhantomData })
+    }
+
+    /// Emits signals for what changed since the previous tick.
+    pub async fn update(&mut self, status: &TimerStatus, events: &[TimerEvent]) {
+        let signals = signals(self.previous.as_ref(), status, events);
+        self.previous = Some(*status);
+
+        let emitter = match SignalEmitter::new(&self.connection, OBJECT_PATH) {
+            Ok(emitter) => emitter,
+            Err(e) => return eprintln!("Failed to emit D-Bus signals: {}", e),
+        };
+        for signal in signals {
+            let sent = match signal {
+                Signal::BreakStarted { break_type, duration } => {
+                    TimerInterface::<P>::break_started(&emitter, break_type.name(), duration).await
+                }
+                Signal::BreakEnded { break_type, completed } => {
+                    TimerInterface::<P>::break_ended(&emitter, break_type.name(), completed).await
+                }
+                Signal::ModeChanged(mode) => {
+                    TimerInterface::<P>::mode_changed(&emitter, mode.name()).await
+                }
+            };
+            if let Err(e) = sent {
+                eprintln!("Failed to emit D-Bus signal: {}", e);
+            }
+        }
+    }
+}
+
+/// Signals for the change from `previous` to `status`. A break ends however
+/// it is left; it only counts as completed when its countdown ran out.
+fn signals(
+    previous: Option<&TimerStatus>,
+    status: &TimerStatus,
+    events: &[TimerEvent],
+) -> Vec<Signal> {
+    let mut signals = Vec::new();
+    let Some(previous) = previous else { return signals };
+
+    let before = previous.active_break.map(|countdown| countdown.break_type);
+    let now = status.active_break.map(|countdown| countdown.break_type);
+    if let Some(break_type) = before.filter(|&before| Some(before) != now) {
+        let completed = events.contains(&TimerEvent::BreakCompleted(break_type));
+        signals.push(Signal::BreakEnded { break_type, completed });
+    }
+    if let Some(countdown) =
+        status.active_break.filter(|countdown| Some(countdown.break_type) != before)
+    {
+        signals.push(Signal::BreakStarted {
+            break_type: countdown.break_type,
+            duration: countdown.duration,
+        });
+    }
+
+    if status.mode != previous.mode {
+        signals.push(Signal::ModeChanged(status.mode));
+    }
+    signals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_bus::PrivateBus;
+    use super::*;
+    use crate::commands::AppState;
+    use crate::stats::StatsStore;
+    use crate::timer::{BreakConfig, TimerService};
+    use futures_util::StreamExt;
+    use std::sync::{Arc, Mutex};
+
+    #[zbus::proxy(
+        interface = "org.rsiassistant.Timer",
+        default_service = "org.rsiassistant.Timer",
+        default_path = "/org/rsiassistant/Timer"
+    )]
+    trait Timer {
+        fn trigger_break(&self, break_type: &str) -> zbus::Result<()>;
+        fn set_mode(&self, mode: &str) -> zbus::Result<()>;
+        fn get_status(&self) -> zbus::Result<String>;
+
+        #[zbus(signal)]
+        fn break_started(&self, break_type: String, duration: u64) -> zbus::Result<()>;
+        #[zbus(signal)]
+        fn mode_changed(&self, mode: String) -> zbus::Result<()>;
+    }
+
+    #[test]
+    fn test_signals_follow_status_changes() {
+        let mut service = TimerService::new(BreakConfig::default());
+        let idle = service.get_status();
+        assert!(signals(None, &idle, &[]).is_empty());
+
+        service.trigger_rest_break();
+        service.tick(true);
+        let on_break = service.get_status();
+        assert_eq!(
+            signals(Some(&idle), &on_break, &[]),
+            [Signal::BreakStarted { break_type: BreakType::Rest, duration: 600 }]
+        );
+
+        service.reset_rest_break();
+        service.set_mode(OperationMode::Quiet);
+        assert_eq!(
+            signals(Some(&on_break), &service.get_status(), &[]),
+            [
+                Signal::BreakEnded { break_type: BreakType::Rest, completed: false },
+                Signal::ModeChanged(OperationMode::Quiet)
+            ]
+        );
+
+        let completed = [TimerEvent::BreakCompleted(BreakType::Rest)];
+        assert_eq!(
+            signals(Some(&on_break), &idle, &completed),
+            [Signal::BreakEnded { break_type: BreakType::Rest, completed: true }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_service_on_private_bus() {
+        let Some(bus) = PrivateBus::start() else { return };
+        let state = Arc::new(AppState {
+            timer_service: Mutex::new(TimerService::new(BreakConfig::default())),
+            stats_store: Mutex::new(StatsStore::default()),
+        });
+        let mut service =
+            DbusService::with_connection(bus.connect().await, state.clone()).await.unwrap();
+        let idle = state.timer_service.lock().unwrap().get_status();
+        service.update(&idle, &[]).await;
+
+        let client = bus.connect().await;
+        let proxy = TimerProxy::new(&client).await.unwrap();
+        let mut started = proxy.receive_break_started().await.unwrap();
+        let mut mode_changed = proxy.receive_mode_changed().await.unwrap();
+
+        proxy.trigger_break("rest").await.unwrap();
+        assert!(proxy.trigger_break("long").await.is_err());
+        proxy.set_mode("Quiet").await.unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&proxy.get_status().await.unwrap()).unwrap();
+        assert_eq!(json["mode"], "quiet");
+
+        let (status, events) = {
+            let mut timer = state.timer_service.lock().unwrap();
+            let events = timer.tick(true);
+            (timer.get_status(), events)
+        };
+        service.update(&status, &events).await;
+
+        let timeout = std::time::Duration::from_secs(2);
+        let signal = tokio::time::timeout(timeout, started.next()).await.unwrap().unwrap();
+        assert_eq!(signal.args().unwrap().break_type, "rest");
+        let signal = tokio::time::timeout(timeout, mode_changed.next()).await.unwrap().unwrap();
+        assert_eq!(signal.args().unwrap().mode, "quiet");
+
+        // A second instance cannot take over the name
+        let other = DbusService::with_connection(bus.connect().await, state.clone()).await;
+        assert!(other.is_err());
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_bus {
+    use std::io::{BufRead, BufReader};
+    use zbus::Connection;
+
+    // A bus of our own, so tests neither need nor disturb the desktop session
+    pub struct PrivateBus {
+        process: std::process::Child,
+        address: String,
+    }
+
+    // Set to skip the tests that need `dbus-daemon` where it is not installed
+    const SKIP_VAR: &str = "RSI_SKIP_DBUS_TESTS";
+
+    impl PrivateBus {
+        /// Starts a bus, or returns `None` to skip the test when `dbus-daemon`
+        /// is missing and `RSI_SKIP_DBUS_TESTS` is set. Panics otherwise.
+        pub fn start() -> Option<Self> {
+            match Self::spawn() {
+                Some(bus) => Some(bus),
+                None if std::env::var_os(SKIP_VAR).is_some() => {
+                    eprintln!("dbus-daemon not available, skipping");
+                    None
+                }
+                None => panic!("dbus-daemon not available; set {} to skip this test", SKIP_VAR),
+            }
+        }
+
+        fn spawn() -> Option<Self> {
+            let mut process = std::process::Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address"])
+                .stdout(std::process::Stdio::piped())
+                .spawn()
+                .ok()?;
+            let mut address = String::new();
+            BufReader::new(process.stdout.as_mut()?).read_line(&mut address).ok()?;
+            Some(Self { process, address: address.trim().to_string() })
+        }
+
+        pub async fn connect(&self) -> Connection {
+            zbus::connection::Builder::address(self.address.as_str())
+                .unwrap()
+                .build()
+                .await
+                .unwrap()
+        }
+    }
+
+    impl Drop for PrivateBus {
+        fn drop(&mut self) {
+            let _ = self.process.kill();
+            let _ = self.process.wait();
+        }
+    }
+}
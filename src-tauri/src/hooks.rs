@@ -0,0 +1,290 @@
+//! User hook commands run on break lifecycle events, with the event described
+//! in `RSI_*` environment variables.
+
+use crate::timer::{BreakType, HookConfig, OperationMode, TimerEvent, TimerStatus};
+use std::time::Duration;
+
+// A timeout of 0 would kill every hook as soon as it starts
+const MIN_TIMEOUT_SECS: u64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HookEvent {
+    Warning(BreakType),
+    BreakStart { break_type: BreakType, duration: u64 },
+    BreakEnd { break_type: BreakType, completed: bool },
+    Skipped(BreakType),
+    DailyLimit,
+}
+
+impl HookEvent {
+    fn name(self) -> &'static str {
+        match self {
+            HookEvent::Warning(_) => "warning",
+            HookEvent::BreakStart { .. } => "break-start",
+            HookEvent::BreakEnd { .. } => "break-end",
+            HookEvent::Skipped(_) => "skipped",
+            HookEvent::DailyLimit => "daily-limit",
+        }
+    }
+
+    fn command(self, config: &HookConfig) -> Option<&str> {
+        let command = match self {
+            HookEvent::Warning(_) => &config.warning,
+            HookEvent::BreakStart { .. } => &config.break_start,
+            HookEvent::BreakEnd { .. } => &config.break_end,
+            HookEvent::Skipped(_) => &config.skipped,
+            HookEvent::DailyLimit => &config.daily_limit,
+        };
+        command.as_deref().filter(|command| !command.trim().is_empty())
+    }
+}
+
+/// A hook due to run, with the environment describing its event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hook {
+    pub event: HookEvent,
+    pub command: String,
+    pub env: Vec<(&'static str, String)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum HookOutcome {
+    Exited(Option<i32>), // no code when killed by a signal
+    TimedOut,
+    Failed(String),
+}
+
+/// Follows the timer from tick to tick and starts the hooks for what happened.
+#[derive(Debug, Default)]
+pub struct Hooks {
+    previous: Option<TimerStatus>,
+}
+
+impl Hooks {
+    /// Starts the hooks configured for this tick in the background. Must be
+    /// called from within the Tokio runtime.
+    pub fn update(&mut self, config: &HookConfig, status: &TimerStatus, events: &[TimerEvent]) {
+        let hooks = due_hooks(config, self.previous.as_ref(), status, events);
+        self.previous = Some(*status);
+
+        let timeout = Duration::from_secs(config.timeout.max(MIN_TIMEOUT_SECS));
+        for hook in hooks {
+            tokio::spawn(async move {
+                match run(&hook, timeout).await {
+                    HookOutcome::Exited(Some(0)) => {
+                        eprintln!("Hook {} exited successfully", hook.event.name())
+                    }
+                    HookOutcome::Exited(Some(code)) => {
+                        eprintln!("Hook {} exited with status {}", hook.event.name(), code)
+                    }
+                    HookOutcome::Exited(None) => {
+                        eprintln!("Hook {} was terminated by a signal", hook.event.name())
+                    }
+                    HookOutcome::TimedOut => eprintln!(
+                        "Hook {} timed out after {}s and was killed",
+                        hook.event.name(),
+                        timeout.as_secs()
+                    ),
+                    HookOutcome::Failed(e) => {
+                        eprintln!("Hook {} failed to start: {}", hook.event.name(), e)
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// The hooks to run for the change from `previous` to `status`. A break ends
+/// however it is left; it only counts as completed when its countdown ran out.
+pub fn due_hooks(
+    config: &HookConfig,
+    previous: Option<&TimerStatus>,
+    status: &TimerStatus,
+    events: &[TimerEvent],
+) -> Vec<Hook> {
+    if config.skip_when_suspended && status.mode == OperationMode::Suspended {
+        return Vec::new();
+    }
+
+    let mut hook_events = Vec::new();
+    let before = previous.and_then(|previous| previous.active_break);
+    if let Some(countdown) = before
+        .filter(|before| status.active_break.map(|now| now.break_type) != Some(before.break_type))
+    {
+        let completed = events.contains(&TimerEvent::BreakCompleted(countdown.break_type));
+        hook_events.push(HookEvent::BreakEnd { break_type: countdown.break_type, completed });
+    }
+    for event in events {
+        match *event {
+            TimerEvent::BreakWarning(break_type) => {
+                hook_events.push(HookEvent::Warning(break_type))
+            }
+            TimerEvent::BreakStarted(break_type) => {
+                let duration = status.active_break.map_or(0, |countdown| countdown.duration);
+                hook_events.push(HookEvent::BreakStart { break_type, duration });
+            }
+            TimerEvent::BreakSkipped(break_type) => {
+                hook_events.push(HookEvent::Skipped(break_type))
+            }
+            TimerEvent::DailyLimitReached => hook_events.push(HookEvent::DailyLimit),
+            _ => {}
+        }
+    }
+
+    hook_events
+        .into_iter()
+        .filter_map(|event| {
+            let command = event.command(config)?.to_string();
+            Some(Hook { event, command, env: environment(event, status) })
+        })
+        .collect()
+}
+
+fn environment(event: HookEvent, status: &TimerStatus) -> Vec<(&'static str, String)> {
+    let mut env = vec![
+        ("RSI_EVENT", event.name().to_string()),
+        ("RSI_MODE", status.mode.name().to_string()),
+        ("RSI_DAILY_USAGE", status.daily_usage.to_string()),
+        ("RSI_DAILY_LIMIT", status.daily_limit.to_string()),
+    ];
+    match event {
+        HookEvent::Warning(break_type) | HookEvent::Skipped(break_type) => {
+            env.push(("RSI_BREAK_TYPE", break_type.name().to_string()));
+        }
+        HookEvent::BreakStart { break_type, duration } => {
+            env.push(("RSI_BREAK_TYPE", break_type.name().to_string()));
+            env.push(("RSI_BREAK_DURATION", duration.to_string()));
+        }
+        HookEvent::BreakEnd { break_type, completed } => {
+            env.push(("RSI_BREAK_TYPE", break_type.name().to_string()));
+            env.push(("RSI_BREAK_COMPLETED", completed.to_string()));
+        }
+        HookEvent::DailyLimit => {}
+    }
+    env
+}
+
+/// Runs a hook through the shell, killing it and any processes it started
+/// once `timeout` has passed.
+pub async fn run(hook: &Hook, timeout: Duration) -> HookOutcome {
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = tokio::process::Command::new("cmd");
+        command.arg("/C").arg(&hook.command);
+        command
+    };
+    #[cfg(not(windows))]
+    let mut command = {
+        let mut command = tokio::process::Command::new("sh");
+        // In a process group of its own, so the whole group can be killed
+        command.arg("-c").arg(&hook.command).process_group(0);
+        command
+    };
+    command.envs(hook.env.iter().map(|(key, value)| (*key, value))).kill_on_drop(true);
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => return HookOutcome::Failed(e.to_string()),
+    };
+    match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(Ok(status)) => HookOutcome::Exited(status.code()),
+        Ok(Err(e)) => HookOutcome::Failed(e.to_string()),
+        Err(_) => {
+            #[cfg(unix)]
+            if let Some(pid) = child.id() {
+                // SAFETY: killpg only sends a signal; the group is the child's own
+                unsafe { libc::killpg(pid as libc::pid_t, libc::SIGKILL) };
+            }
+            let _ = child.kill().await;
+            HookOutcome::TimedOut
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::{BreakConfig, TimerService};
+
+    fn config() -> HookConfig {
+        HookConfig {
+            break_start: Some("pause-music".to_string()),
+            break_end: Some("resume-music".to_string()),
+            skipped: Some("resume-music".to_string()),
+            ..HookConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_hooks_for_break_lifecycle() {
+        let mut service = TimerService::new(BreakConfig::default());
+        let idle = service.get_status();
+
+        service.trigger_rest_break();
+        let events = service.tick(true);
+        let on_break = service.get_status();
+        let hooks = due_hooks(&config(), Some(&idle), &on_break, &events);
+        assert_eq!(hooks.len(), 1);
+        assert_eq!(hooks[0].command, "pause-music");
+        assert!(hooks[0].env.contains(&("RSI_EVENT", "break-start".to_string())));
+        assert!(hooks[0].env.contains(&("RSI_BREAK_DURATION", "600".to_string())));
+
+        service.skip_break(BreakType::Rest).unwrap();
+        let events = service.tick(true);
+        let hooks = due_hooks(&config(), Some(&on_break), &service.get_status(), &events);
+        let names: Vec<_> = hooks.iter().map(|hook| hook.event.name()).collect();
+        assert_eq!(names, ["break-end", "skipped"]);
+        assert!(hooks[0].env.contains(&("RSI_BREAK_COMPLETED", "false".to_string())));
+    }
+
+    #[test]
+    fn test_unset_and_suspended_hooks_do_not_run() {
+        let status = TimerService::new(BreakConfig::default()).get_status();
+        let events = [TimerEvent::BreakWarning(BreakType::Micro), TimerEvent::DailyLimitReached];
+        assert!(due_hooks(&config(), None, &status, &events).is_empty());
+
+        let mut config = config();
+        config.daily_limit = Some("notify-send done".to_string());
+        assert_eq!(due_hooks(&config, None, &status, &events).len(), 1);
+
+        let mut service = TimerService::new(BreakConfig::default());
+        service.set_mode(OperationMode::Suspended);
+        config.skip_when_suspended = true;
+        assert!(due_hooks(&config, None, &service.get_status(), &events).is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_reports_exit_status_and_timeout() {
+        let hook = |command: &str| Hook {
+            event: HookEvent::DailyLimit,
+            command: command.to_string(),
+            env: vec![("RSI_EVENT", "daily-limit".to_string())],
+        };
+        let timeout = Duration::from_secs(5);
+
+        let outcome = run(&hook(r#"test "$RSI_EVENT" = daily-limit && exit 3"#), timeout).await;
+        assert_eq!(outcome, HookOutcome::Exited(Some(3)));
+
+        let outcome = run(&hook("sleep 5"), Duration::from_millis(100)).await;
+        assert_eq!(outcome, HookOutcome::TimedOut);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_timeout_kills_processes_started_by_hook() {
+        let marker =
+            std::env::temp_dir().join(format!("rsi-assistant-hook-{}", std::process::id()));
+        let _ = std::fs::remove_file(&marker);
+        let hook = Hook {
+            event: HookEvent::DailyLimit,
+            command: format!("(sleep 1; touch '{}') & sleep 5", marker.display()),
+            env: Vec::new(),
+        };
+
+        let outcome = run(&hook, Duration::from_millis(100)).await;
+        assert_eq!(outcome, HookOutcome::TimedOut);
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!marker.exists());
+    }
+}
@@ -0,0 +1,151 @@
+//! Telling user input from noise in polled device state.
+
+use crate::timer::InputConfig;
+#[cfg(test)]
+use std::collections::VecDeque;
+
+/// Device state read in one poll.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputSample {
+    pub coords: (i32, i32),
+    pub buttons: Vec<bool>, // pressed state per mouse button
+    pub keys_pressed: bool,
+}
+
+/// Mouse and keyboard state the idle detector polls. Sources are built on the
+/// polling thread and stay there, so they need not be `Send`.
+pub trait InputSource: 'static {
+    fn mouse_coords(&self) -> (i32, i32);
+    fn mouse_buttons(&self) -> Vec<bool>;
+    fn keys_pressed(&self) -> bool;
+
+    fn sample(&mut self) -> InputSample {
+        InputSample {
+            coords: self.mouse_coords(),
+            buttons: self.mouse_buttons(),
+            keys_pressed: self.keys_pressed(),
+        }
+    }
+}
+
+/// Plays back a fixed run of samples, then holds the last one.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct ScriptedSource {
+    script: VecDeque<InputSample>,
+    current: InputSample,
+}
+
+#[cfg(test)]
+impl ScriptedSource {
+    pub fn new(script: impl IntoIterator<Item = InputSample>) -> Self {
+        Self { script: script.into_iter().collect(), ..Self::default() }
+    }
+}
+
+#[cfg(test)]
+impl InputSource for ScriptedSource {
+    fn mouse_coords(&self) -> (i32, i32) {
+        self.current.coords
+    }
+
+    fn mouse_buttons(&self) -> Vec<bool> {
+        self.current.buttons.clone()
+    }
+
+    fn keys_pressed(&self) -> bool {
+        self.current.keys_pressed
+    }
+
+    fn sample(&mut self) -> InputSample {
+        if let Some(next) = self.script.pop_front() {
+            self.current = next;
+        }
+        self.current.clone()
+    }
+}
+
+/// Decides which polls show the user at work. Pointer movement is measured
+/// from the last position that counted, so slow deliberate moves add up while
+/// jitter around one spot never does.
+#[derive(Debug, Default)]
+pub struct ActivityFilter {
+    anchor: Option<(i32, i32)>,
+}
+
+impl ActivityFilter {
+    pub fn is_activity(&mut self, sample: &InputSample, config: &InputConfig) -> bool {
+        let pressed = sample.keys_pressed
+            || config.mouse_buttons && sample.buttons.iter().any(|&pressed| pressed);
+
+        let anchor = *self.anchor.get_or_insert(sample.coords);
+        let dx = i64::from(sample.coords.0) - i64::from(anchor.0);
+        let dy = i64::from(sample.coords.1) - i64::from(anchor.1);
+        let threshold = i64::from(config.jitter_threshold);
+        let moved = sample.coords != anchor && dx * dx + dy * dy >= threshold * threshold;
+        if moved {
+            self.anchor = Some(sample.coords);
+        }
+        moved || pressed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(x: i32, y: i32) -> InputSample {
+        InputSample { coords: (x, y), ..InputSample::default() }
+    }
+
+    /// Which polls of the script count as activity.
+    fn replay(script: Vec<InputSample>, config: &InputConfig) -> Vec<bool> {
+        let polls = script.len();
+        let mut source = ScriptedSource::new(script);
+        let mut filter = ActivityFilter::default();
+        (0..polls).map(|_| filter.is_activity(&source.sample(), config)).collect()
+    }
+
+    #[test]
+    fn test_jitter_ignored_until_threshold() {
+        let config = InputConfig { mouse_buttons: true, jitter_threshold: 3 };
+        let script = vec![
+            at(100, 100),
+            // Vibration around one spot
+            at(101, 100),
+            at(99, 101),
+            at(100, 102),
+            at(102, 98),
+            // A slow move adds up from where the pointer last counted
+            at(102, 101),
+            at(103, 100),
+            at(104, 101),
+        ];
+        let expected = [false, false, false, false, false, false, true, false];
+        assert_eq!(replay(script, &config), expected);
+
+        let exact = InputConfig { jitter_threshold: 0, ..config };
+        assert_eq!(replay(vec![at(0, 0), at(1, 0), at(1, 0)], &exact), [false, true, false]);
+    }
+
+    #[test]
+    fn test_held_button_and_keys_count() {
+        let config = InputConfig { mouse_buttons: true, jitter_threshold: 3 };
+        let dragging = InputSample { buttons: vec![false, true], ..at(5, 5) };
+        let typing = InputSample { keys_pressed: true, ..at(5, 5) };
+        let script = vec![dragging.clone(), dragging, typing, at(5, 5)];
+        assert_eq!(replay(script.clone(), &config), [true, true, true, false]);
+
+        let ignored = InputConfig { mouse_buttons: false, ..config };
+        assert_eq!(replay(script, &ignored), [false, false, true, false]);
+    }
+
+    #[test]
+    fn test_scripted_source_holds_last_sample() {
+        let mut source = ScriptedSource::new([at(1, 2), at(3, 4)]);
+        assert_eq!(source.sample(), at(1, 2));
+        assert_eq!(source.sample(), at(3, 4));
+        assert_eq!(source.sample(), at(3, 4));
+        assert_eq!(source.mouse_coords(), (3, 4));
+    }
+}
@@ -0,0 +1,260 @@
+//! Lock screen and sleep awareness from systemd-logind: a locked or sleeping
+//! session counts as idle whatever input is seen, and time asleep, which the
+//! timer cannot tick through, is added as idle time on waking.
+
+use crate::timer::TimerService;
+use futures_util::future;
+use futures_util::stream::{self, StreamExt};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use zbus::zvariant::OwnedObjectPath;
+use zbus::Connection;
+
+#[zbus::proxy(
+    interface = "org.freedesktop.login1.Manager",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1"
+)]
+trait Manager {
+    fn get_session(&self, session_id: &str) -> zbus::Result<OwnedObjectPath>;
+
+    #[zbus(signal)]
+    fn prepare_for_sleep(&self, start: bool) -> zbus::Result<()>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.login1.Session",
+    default_service = "org.freedesktop.login1"
+)]
+trait Session {
+    #[zbus(signal)]
+    fn lock(&self) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn unlock(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn locked_hint(&self) -> zbus::Result<bool>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SessionSignal {
+    Lock,
+    Unlock,
+    PrepareForSleep(bool), // true going to sleep, false on waking
+}
+
+/// Whether the session is locked or asleep, and the seconds spent asleep not
+/// yet handed to the timer. Times are wall clock seconds, as the monotonic
+/// clock stops during sleep.
+#[derive(Debug, Default)]
+pub struct AwayTracker {
+    locked: bool,
+    asleep_since: Option<u64>,
+    slept: u64,
+}
+
+impl AwayTracker {
+    pub fn handle(&mut self, signal: SessionSignal, now: u64) {
+        match signal {
+            SessionSignal::Lock => self.locked = true,
+            SessionSignal::Unlock => self.locked = false,
+            SessionSignal::PrepareForSleep(true) => {
+                self.asleep_since.get_or_insert(now);
+            }
+            SessionSignal::PrepareForSleep(false) => {
+                if let Some(since) = self.asleep_since.take() {
+                    self.slept += now.saturating_sub(since);
+                }
+            }
+        }
+    }
+
+    pub fn is_away(&self) -> bool {
+        self.locked || self.asleep_since.is_some()
+    }
+
+    pub fn take_slept(&mut self) -> u64 {
+        std::mem::take(&mut self.slept)
+    }
+}
+
+/// Follows the Lock, Unlock and PrepareForSleep signals of the user's session.
+pub struct LogindMonitor {
+    tracker: Arc<Mutex<AwayTracker>>,
+}
+
+impl LogindMonitor {
+    pub async fn connect() -> zbus::Result<Self> {
+        Self::with_connection(&Connection::system().await?).await
+    }
+
+    /// Subscribes to the session's signals and follows them in the background.
+    /// Must be called from within the Tokio runtime.
+    pub async fn with_connection(connection: &Connection) -> zbus::Result<Self> {
+        let manager = ManagerProxy::new(connection).await?;
+        // "auto" is the caller's session, or the user's graphical one for a
+        // process started outside it, such as a user service
+        let session_id = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "auto".to_string());
+        let path = manager.get_session(&session_id).await?;
+        let session = SessionProxy::builder(connection).path(path)?.build().await?;
+
+        // Subscribe before reading the lock state, so no change is missed
+        let sleep = manager.receive_prepare_for_sleep().await?.filter_map(|signal| {
+            let start = signal.args().ok().map(|args| SessionSignal::PrepareForSleep(args.start));
+            future::ready(start)
+        });
+        let lock = session.receive_lock().await?.map(|_| SessionSignal::Lock);
+        let unlock = session.receive_unlock().await?.map(|_| SessionSignal::Unlock);
+        let mut signals = stream::select(sleep, stream::select(lock, unlock));
+
+        let mut tracker = AwayTracker::default();
+        if session.locked_hint().await.unwrap_or(false) {
+            tracker.handle(SessionSignal::Lock, now());
+        }
+        let tracker = Arc::new(Mutex::new(tracker));
+
+        let listener = tracker.clone();
+        tokio::spawn(async move {
+            while let Some(signal) = signals.next().await {
+                listener.lock().unwrap().handle(signal, now());
+            }
+            eprintln!("Lost the logind session signals");
+        });
+
+        Ok(Self { tracker })
+    }
+
+    /// Hands the timer the time slept since the last call and whether the
+    /// session is locked or asleep now, which the caller should treat as idle.
+    /// Does nothing when `lock_screen_idle` is off.
+    pub fn apply(&self, service: &mut TimerService) -> bool {
+        let (slept, away) = {
+            let mut tracker = self.tracker.lock().unwrap();
+            (tracker.take_slept(), tracker.is_away())
+        };
+        if !service.config.lock_screen_idle {
+            service.set_away(false);
+            return false;
+        }
+
+        if slept > 0 {
+            service.set_away(true);
+            service.add_idle_time(slept);
+        }
+        service.set_away(away);
+        away
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::{BreakConfig, BreakType, TimerEvent};
+    use std::time::Duration;
+    use tokio::net::UnixStream;
+    use zbus::object_server::SignalEmitter;
+
+    const SESSION_PATH: &str = "/org/freedesktop/login1/session/_32";
+
+    // Stand-ins for logind's manager and session objects
+    struct StandInManager;
+
+    #[zbus::interface(name = "org.freedesktop.login1.Manager")]
+    impl StandInManager {
+        fn get_session(&self, _session_id: &str) -> OwnedObjectPath {
+            OwnedObjectPath::try_from(SESSION_PATH).unwrap()
+        }
+
+        #[zbus(signal)]
+        async fn prepare_for_sleep(emitter: &SignalEmitter<'_>, start: bool) -> zbus::Result<()>;
+    }
+
+    struct StandInSession;
+
+    #[zbus::interface(name = "org.freedesktop.login1.Session")]
+    impl StandInSession {
+        #[zbus(property)]
+        fn locked_hint(&self) -> bool {
+            false
+        }
+
+        #[zbus(signal)]
+        async fn lock(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+
+        #[zbus(signal)]
+        async fn unlock(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+    }
+
+    async fn connect_to_stand_in() -> (Connection, Connection) {
+        let (server_stream, client_stream) = UnixStream::pair().unwrap();
+        let guid = zbus::Guid::generate();
+        let server = zbus::connection::Builder::unix_stream(server_stream)
+            .server(guid)
+            .unwrap()
+            .p2p()
+            .serve_at("/org/freedesktop/login1", StandInManager)
+            .unwrap()
+            .serve_at(SESSION_PATH, StandInSession)
+            .unwrap()
+            .build();
+        let client = zbus::connection::Builder::unix_stream(client_stream).p2p().build();
+        let (server, client) = tokio::join!(server, client);
+        (server.unwrap(), client.unwrap())
+    }
+
+    #[test]
+    fn test_tracker_counts_sleep() {
+        let mut tracker = AwayTracker::default();
+        tracker.handle(SessionSignal::Lock, 100);
+        tracker.handle(SessionSignal::PrepareForSleep(true), 160);
+        assert!(tracker.is_away());
+
+        tracker.handle(SessionSignal::PrepareForSleep(false), 3760);
+        assert!(tracker.is_away());
+        assert_eq!(tracker.take_slept(), 3600);
+        assert_eq!(tracker.take_slept(), 0);
+
+        tracker.handle(SessionSignal::Unlock, 3790);
+        assert!(!tracker.is_away());
+    }
+
+    #[tokio::test]
+    async fn test_monitor_follows_stand_in_session() {
+        let (server, client) = connect_to_stand_in().await;
+        let monitor = LogindMonitor::with_connection(&client).await.unwrap();
+        let mut service = TimerService::new(BreakConfig::default());
+        assert!(!monitor.apply(&mut service));
+
+        let emitter = SignalEmitter::new(&server, SESSION_PATH).unwrap();
+        StandInSession::lock(&emitter).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(monitor.apply(&mut service));
+
+        // The lock lasted a microbreak
+        for _ in 0..30 {
+            service.tick(true);
+        }
+        StandInSession::unlock(&emitter).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!monitor.apply(&mut service));
+        assert_eq!(service.tick(false), [TimerEvent::NaturalBreak(BreakType::Micro)]);
+
+        let manager = SignalEmitter::new(&server, "/org/freedesktop/login1").unwrap();
+        StandInManager::prepare_for_sleep(&manager, true).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(monitor.apply(&mut service));
+        StandInManager::prepare_for_sleep(&manager, false).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!monitor.apply(&mut service));
+
+        service.config.lock_screen_idle = false;
+        StandInSession::lock(&emitter).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!monitor.apply(&mut service));
+    }
+}
@@ -1,4 +1,13 @@
+mod activity;
+#[cfg(target_os = "linux")]
+pub mod logind;
+
+pub use activity::InputSource;
+
+use crate::timer::InputConfig;
+use activity::{ActivityFilter, InputSample};
 use device_query::{DeviceQuery, DeviceState};
+use std::marker::PhantomData;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -7,53 +16,112 @@ pub trait IdleDetector: Send + Sync {
     fn get_seconds_since_last_input(&self) -> u64;
 }
 
-pub struct DeviceQueryIdleDetector {
+impl InputSource for DeviceState {
+    fn mouse_coords(&self) -> (i32, i32) {
+        self.get_mouse().coords
+    }
+
+    fn mouse_buttons(&self) -> Vec<bool> {
+        self.get_mouse().button_pressed
+    }
+
+    fn keys_pressed(&self) -> bool {
+        !self.get_keys().is_empty()
+    }
+
+    // The pointer's position and buttons come from one query
+    fn sample(&mut self) -> InputSample {
+        let mouse = self.get_mouse();
+        InputSample {
+            coords: mouse.coords,
+            buttons: mouse.button_pressed,
+            keys_pressed: self.keys_pressed(),
+        }
+    }
+}
+
+/// Reads a source and records when it last showed the user at work.
+struct Poller<S> {
+    source: S,
+    filter: ActivityFilter,
     last_activity: Arc<Mutex<u64>>,
+    config: Arc<Mutex<InputConfig>>,
+}
+
+impl<S: InputSource> Poller<S> {
+    fn new(source: S, last_activity: Arc<Mutex<u64>>, config: Arc<Mutex<InputConfig>>) -> Self {
+        Self { source, filter: ActivityFilter::default(), last_activity, config }
+    }
+
+    /// Takes one sample, recording `now` (milliseconds) if it counts as activity.
+    fn poll(&mut self, now: u64) {
+        let sample = self.source.sample();
+        let config = *self.config.lock().unwrap();
+        if self.filter.is_activity(&sample, &config) {
+            *self.last_activity.lock().unwrap() = now;
+        }
+    }
+}
+
+pub struct DeviceQueryIdleDetector<S: InputSource = DeviceState> {
+    last_activity: Arc<Mutex<u64>>, // milliseconds since the epoch
+    config: Arc<Mutex<InputConfig>>,
     // We keep the thread handle to ensure it lives as long as the struct
     _polling_thread: Option<thread::JoinHandle<()>>,
+    _source: PhantomData<fn() -> S>,
 }
 
 impl DeviceQueryIdleDetector {
-    pub fn new() -> Self {
-        let last_activity = Arc::new(Mutex::new(Self::now()));
+    pub fn new(config: InputConfig) -> Self {
+        Self::with_source(config, DeviceState::new)
+    }
+}
+
+impl<S: InputSource> DeviceQueryIdleDetector<S> {
+    /// Polls a source built on the polling thread, as some device backends
+    /// cannot move between threads.
+    pub fn with_source<F>(config: InputConfig, make_source: F) -> Self
+    where
+        F: FnOnce() -> S + Send + 'static,
+    {
+        let last_activity = Arc::new(Mutex::new(now_millis()));
         let last_activity_clone = last_activity.clone();
+        let config = Arc::new(Mutex::new(config));
+        let config_clone = config.clone();
 
         let thread_handle = thread::spawn(move || {
-            let device_state = DeviceState::new();
-            let mut last_mouse_pos = device_state.get_mouse().coords;
+            let mut poller = Poller::new(make_source(), last_activity_clone, config_clone);
 
             loop {
                 thread::sleep(Duration::from_millis(100));
-
-                let mouse = device_state.get_mouse();
-                let keys = device_state.get_keys();
-
-                if mouse.coords != last_mouse_pos || !keys.is_empty() {
-                    let mut guard = last_activity_clone.lock().unwrap();
-                    *guard = Self::now();
-                    last_mouse_pos = mouse.coords;
-                }
+                poller.poll(now_millis());
             }
         });
 
-        Self { last_activity, _polling_thread: Some(thread_handle) }
+        Self { last_activity, config, _polling_thread: Some(thread_handle), _source: PhantomData }
     }
 
-    fn now() -> u64 {
-        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    /// Applies changed input settings to the polling thread.
+    pub fn set_config(&self, config: InputConfig) {
+        *self.config.lock().unwrap() = config;
     }
 }
 
-impl IdleDetector for DeviceQueryIdleDetector {
+impl<S: InputSource> IdleDetector for DeviceQueryIdleDetector<S> {
     fn get_seconds_since_last_input(&self) -> u64 {
         let last = *self.last_activity.lock().unwrap();
-        Self::now().saturating_sub(last)
+        now_millis().saturating_sub(last) / 1000
     }
 }
 
+fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use activity::ScriptedSource;
 
     struct MockIdleDetector {
         last_input_secs_ago: u64,
@@ -72,10 +140,28 @@ mod tests {
     }
 
     #[test]
-    fn test_device_query_init() {
-        let detector = DeviceQueryIdleDetector::new();
-        let idle = detector.get_seconds_since_last_input();
-        // Just verify it doesn't panic and returns a sane value
-        assert!(idle < 1000);
+    fn test_poller_follows_source() {
+        let config = Arc::new(Mutex::new(InputConfig::default()));
+        let last_activity = Arc::new(Mutex::new(0));
+        let typing = InputSample { keys_pressed: true, ..InputSample::default() };
+        let mut poller = Poller::new(
+            ScriptedSource::new([typing, InputSample::default()]),
+            last_activity.clone(),
+            config.clone(),
+        );
+        poller.poll(1_000);
+        assert_eq!(*last_activity.lock().unwrap(), 1_000);
+        // Keys released with the pointer still
+        poller.poll(1_100);
+        assert_eq!(*last_activity.lock().unwrap(), 1_000);
+
+        // Sub-threshold jitter, then a still pointer
+        let jitter = [(0, 0), (1, 0), (0, 1), (1, 1)]
+            .map(|coords| InputSample { coords, ..InputSample::default() });
+        let mut poller = Poller::new(ScriptedSource::new(jitter), last_activity.clone(), config);
+        for now in [1_200, 1_300, 1_400, 1_500, 1_600] {
+            poller.poll(now);
+        }
+        assert_eq!(*last_activity.lock().unwrap(), 1_000);
     }
 }
@@ -1,101 +1,138 @@
+#[cfg(target_os = "linux")]
+mod busy;
+mod calendar;
+pub mod cli;
 mod commands;
+pub mod daemon;
+#[cfg(target_os = "linux")]
+mod dbus;
+mod hooks;
 mod idle;
+mod messages;
+mod notifications;
+mod overlay;
+mod persistence;
+mod pipeline;
+#[cfg(unix)]
+mod rpc;
+mod shortcuts;
 mod stats;
+mod statusbar;
 mod timer;
+mod tray;
 
+use crate::cli::{CliCommand, CliRequest};
 use crate::commands::AppState;
-use crate::idle::DeviceQueryIdleDetector;
+use crate::overlay::{
+    is_overlay_label, overlay_index, overlay_label, BreakPayload, MonitorArea, OverlayCommand,
+    OverlayManager, OverlayWindows,
+};
+use crate::pipeline::{FallbackNotifier, TickPipeline};
+use crate::shortcuts::{parse_bindings, ShortcutAction};
 use crate::stats::StatsStore;
-use crate::timer::{BreakConfig, TimerService};
+use crate::timer::{BreakAction, BreakConfig, BreakType, ShortcutConfig, TimerService};
+use crate::tray::{parse_menu_id, MenuAction, TrayMenu, TrayState, ICON_SIZE};
 use chrono::Datelike;
 use std::sync::Mutex;
 use std::time::Duration;
-use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::image::Image;
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::tray::TrayIconBuilder;
-use tauri::{Emitter, Manager};
+use tauri::webview::PageLoadEvent;
+use tauri::{
+    AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder, Wry,
+};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
 use tokio::time::sleep;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        // Must come first so that a second launch exits before setting anything up
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            match cli::parse_args(&argv) {
+                Ok(Some(request)) => run_cli(app, request),
+                // A plain second launch brings the main window forward
+                _ => {
+                    if let Some(win) = app.get_webview_window("main") {
+                        let _ = win.show();
+                        let _ = win.set_focus();
+                    }
+                }
+            }
+        }))
         .plugin(tauri_plugin_autostart::Builder::new().build())
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .setup(|app| {
-            // Initialize services
-            let idle_detector = DeviceQueryIdleDetector::new();
-            let timer_service = TimerService::new(BreakConfig::default());
+            // Settings and history are shared with the headless daemon
+            let data_dir = app.path().app_data_dir()?;
+            match persistence::lock_data_dir(&data_dir) {
+                Ok(lock) => {
+                    app.manage(lock);
+                }
+                Err(e) => {
+                    // A running app would have taken this launch through the
+                    // single-instance plugin, so the daemon holds the lock, and
+                    // a command the API socket did not reach cannot be carried out
+                    let args: Vec<String> = std::env::args().collect();
+                    if let Ok(Some(CliRequest { reply: Some(reply), .. })) = cli::parse_args(&args)
+                    {
+                        cli::send_reply(&reply, &Err(cli::DAEMON_WITHOUT_API.to_string()));
+                    } else {
+                        eprintln!("{}", e);
+                    }
+                    std::process::exit(1);
+                }
+            }
+            let config = persistence::load_settings(&data_dir).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                None
+            });
+            let timer_service = TimerService::new(config.unwrap_or_default());
+            let stats_store = persistence::load_stats(&data_dir).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                StatsStore::default()
+            });
+
+            // A shortcut another application already holds should not stop startup
+            if let Err(e) = register_shortcuts(app.handle(), &timer_service.config.shortcuts) {
+                eprintln!("Failed to register global shortcuts: {}", e);
+            }
 
             // Manage state
             app.manage(AppState {
                 timer_service: Mutex::new(timer_service),
-                stats_store: Mutex::new(StatsStore::default()),
+                stats_store: Mutex::new(stats_store),
             });
 
-            // System Tray Setup - Comprehensive Menu
-            use tauri::menu::CheckMenuItem;
+            // This is the first instance, so a CLI command given at launch is
+            // carried out here
+            let args: Vec<String> = std::env::args().collect();
+            if let Ok(Some(request)) = cli::parse_args(&args) {
+                if let (CliCommand::Status { .. }, Some(reply)) = (&request.command, &request.reply)
+                {
+                    cli::send_reply(reply, &Err("RSI Assistant is not running".to_string()));
+                    std::process::exit(1);
+                }
+                run_cli(app.handle(), request);
+            }
+
+            // System Tray Setup
             use tauri_plugin_dialog::DialogExt;
 
-            // System Tray Setup - Comprehensive Menu
-            // TODO: Use IconMenuItem once we have distinct icons. For now using standard MenuItem with placeholders where icons would be.
-            let show_i = MenuItem::with_id(app, "show", "Show RSI Assistant", true, None::<&str>)?;
-            let rest_break_i =
-                MenuItem::with_id(app, "rest_break", "Take Rest Break Now", true, None::<&str>)?;
-            let exercises_i = MenuItem::with_id(app, "exercises", "Exercises", true, None::<&str>)?;
-            let statistics_i =
-                MenuItem::with_id(app, "statistics", "Statistics", true, None::<&str>)?;
-
-            // Operation Mode submenu
-            // Default to Normal being checked
-            let mode_normal_i =
-                CheckMenuItem::with_id(app, "mode_normal", "Normal", true, true, None::<&str>)?;
-            let mode_quiet_i =
-                CheckMenuItem::with_id(app, "mode_quiet", "Quiet", true, false, None::<&str>)?;
-            let mode_suspended_i = CheckMenuItem::with_id(
-                app,
-                "mode_suspended",
-                "Suspended",
-                true,
-                false,
-                None::<&str>,
-            )?;
-
-            let mode_submenu = Submenu::with_items(
-                app,
-                "Mode",
-                true,
-                &[&mode_normal_i, &mode_quiet_i, &mode_suspended_i],
-            )?;
-
-            let preferences_i =
-                MenuItem::with_id(app, "preferences", "Preferences", true, None::<&str>)?;
-            let about_i = MenuItem::with_id(app, "about", "About", true, None::<&str>)?;
-            let quit_i = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-
-            let menu = Menu::with_items(
-                app,
-                &[
-                    &show_i,
-                    &PredefinedMenuItem::separator(app)?,
-                    &rest_break_i,
-                    &exercises_i,
-                    &statistics_i,
-                    &PredefinedMenuItem::separator(app)?,
-                    &mode_submenu,
-                    &preferences_i,
-                    &about_i,
-                    &PredefinedMenuItem::separator(app)?,
-                    &quit_i,
-                ],
-            )?;
-
-            // Capture handles for event closure
-            let mode_normal_handle = mode_normal_i.clone();
-            let mode_quiet_handle = mode_quiet_i.clone();
-            let mode_suspended_handle = mode_suspended_i.clone();
+            // Entries that depend on timer state are rebuilt by the background task
+            let tray_menu = {
+                let state = app.state::<AppState>();
+                let service = state.timer_service.lock().unwrap();
+                TrayMenu::from_service(&service)
+            };
+            let menu = build_tray_menu(app.handle(), &tray_menu)?;
 
             let _tray = TrayIconBuilder::with_id("tray")
                 .menu(&menu)
@@ -103,9 +140,34 @@ pub fn run() {
                 .show_menu_on_left_click(true)
                 .on_menu_event(move |app, event| {
                     let window = app.get_webview_window("main");
+                    let state = app.state::<AppState>();
+
+                    if let Some(action) = parse_menu_id(event.id.as_ref()) {
+                        let result = match action {
+                            MenuAction::Break(break_type, action) => {
+                                state.apply_break_action(break_type, action)
+                            }
+                            MenuAction::SuspendFor(seconds) => {
+                                state.timer_service.lock().unwrap().suspend_for(seconds);
+                                Ok(())
+                            }
+                            MenuAction::Resume => {
+                                state.timer_service.lock().unwrap().resume();
+                                Ok(())
+                            }
+                        };
+                        if let Err(e) = result {
+                            eprintln!("Failed to apply tray action: {}", e);
+                        }
+                    }
 
                     match event.id.as_ref() {
                         "quit" => {
+                            if let Ok(dir) = app.path().app_data_dir() {
+                                if let Err(e) = state.save_stats(&dir) {
+                                    eprintln!("{}", e);
+                                }
+                            }
                             app.exit(0);
                         }
                         "show" => {
@@ -130,15 +192,6 @@ pub fn run() {
                             );
                             let _ = app.dialog().message(about_message);
                         }
-                        "rest_break" => {
-                            if let Some(win) = window {
-                                let _ = win.show();
-                                let _ = win.set_focus();
-                            }
-                            let state = app.state::<AppState>();
-                            let mut service = state.timer_service.lock().unwrap();
-                            service.trigger_rest_break();
-                        }
                         "exercises" => {
                             if let Some(win) = window {
                                 let _ = win.show();
@@ -161,110 +214,114 @@ pub fn run() {
                             }
                         }
                         "mode_normal" => {
-                            let state = app.state::<AppState>();
                             let mut service = state.timer_service.lock().unwrap();
                             service.set_mode(timer::OperationMode::Normal);
-
-                            let _ = mode_normal_handle.set_checked(true);
-                            let _ = mode_quiet_handle.set_checked(false);
-                            let _ = mode_suspended_handle.set_checked(false);
                         }
                         "mode_quiet" => {
-                            let state = app.state::<AppState>();
                             let mut service = state.timer_service.lock().unwrap();
                             service.set_mode(timer::OperationMode::Quiet);
-
-                            let _ = mode_normal_handle.set_checked(false);
-                            let _ = mode_quiet_handle.set_checked(true);
-                            let _ = mode_suspended_handle.set_checked(false);
                         }
                         "mode_suspended" => {
-                            let state = app.state::<AppState>();
                             let mut service = state.timer_service.lock().unwrap();
                             service.set_mode(timer::OperationMode::Suspended);
-
-                            let _ = mode_normal_handle.set_checked(false);
-                            let _ = mode_quiet_handle.set_checked(false);
-                            let _ = mode_suspended_handle.set_checked(true);
                         }
                         _ => {}
                     }
+
+                    // Check marks and entries reflect the new state right away
+                    let tray_menu = TrayMenu::from_service(&state.timer_service.lock().unwrap());
+                    if let Err(e) = refresh_tray_menu(app, &tray_menu) {
+                        eprintln!("Failed to rebuild tray menu: {}", e);
+                    }
                 })
                 .build(app)?;
 
             // Clone handle for background task
             let handle = app.handle().clone();
 
-            use tauri_plugin_notification::NotificationExt;
-
-            // ... imports ...
-
-            // ... inside run() setup ...
-
             // Spawn background task
             tauri::async_runtime::spawn(async move {
-                use crate::idle::IdleDetector;
+                use tauri_plugin_notification::NotificationExt;
 
-                let mut was_micro_overdue = false;
-                let mut was_rest_overdue = false;
+                let notification_handle = handle.clone();
+                let fallback: FallbackNotifier = std::sync::Arc::new(move |title, body| {
+                    let builder = notification_handle.notification().builder();
+                    let _ = builder.title(title).body(body).show();
+                });
+                let mut pipeline =
+                    TickPipeline::connect(handle.clone(), data_dir, None, fallback).await;
+                let mut overlay_manager = OverlayManager::default();
+                let mut tray_state = TrayState::default();
+                let mut tray_menu = None;
 
                 loop {
                     sleep(Duration::from_secs(1)).await;
 
-                    let idle_seconds = idle_detector.get_seconds_since_last_input();
-                    let is_idle = idle_seconds > 5; // Simple threshold
-
-                    let status = {
+                    let status = pipeline.tick().await.status;
+                    let (payload, focus_policy, menu) = {
                         let state = handle.state::<AppState>();
-                        let mut service = state.timer_service.lock().unwrap();
-                        service.tick(is_idle);
-                        let status = service.get_status();
-
-                        // Update statistics with current usage
-                        let mut stats = state.stats_store.lock().unwrap();
-                        let today = stats.get_or_create_today();
-                        today.total_usage_seconds = status.daily_usage;
-
-                        status
+                        let service = state.timer_service.lock().unwrap();
+                        (
+                            BreakPayload::from_service(&service),
+                            service.config.overlay_focus,
+                            TrayMenu::from_service(&service),
+                        )
                     };
 
-                    // Notifications
-                    if status.micro_is_overdue && !was_micro_overdue {
-                        let _ = handle
-                            .notification()
-                            .builder()
-                            .title("Microbreak Time")
-                            .body("Take a short 30s break!")
-                            .show();
-                    }
-                    if status.rest_is_overdue && !was_rest_overdue {
-                        let _ = handle
-                            .notification()
-                            .builder()
-                            .title("Rest Break Time")
-                            .body("Time for a longer rest.")
-                            .show();
-                    }
-                    was_micro_overdue = status.micro_is_overdue;
-                    was_rest_overdue = status.rest_is_overdue;
-
                     // Emit event to frontend
                     if let Err(e) = handle.emit("timer-update", status) {
                         eprintln!("Failed to emit timer update: {}", e);
                     }
 
-                    // Manage Overlay Window
-                    if let Some(overlay) = handle.get_webview_window("overlay") {
-                        let should_show = status.micro_is_overdue || status.rest_is_overdue;
-                        // To avoid excessive IPC calls, we could track state, but for now simple show/hide logic
-                        if should_show {
-                            // Ensure it's visible and on top
-                            // We use unwrap_or to ignore errors gracefully in the loop
-                            let _ = overlay.show();
-                            let _ = overlay.set_focus();
-                            let _ = overlay.set_always_on_top(true);
-                        } else {
-                            let _ = overlay.hide();
+                    // Tray icon and tooltip follow break progress
+                    if let Some(tray) = handle.tray_by_id("tray") {
+                        let (icon, tooltip) = tray_state.update(&status);
+                        if let Some(rgba) = icon {
+                            let icon = Image::new_owned(rgba, ICON_SIZE, ICON_SIZE);
+                            if let Err(e) = tray.set_icon(Some(icon)) {
+                                eprintln!("Failed to update tray icon: {}", e);
+                            }
+                        }
+                        if let Some(tooltip) = tooltip {
+                            let _ = tray.set_tooltip(Some(tooltip));
+                        }
+                    }
+
+                    // Tray entries show time remaining, so they are rebuilt as it changes
+                    if tray_menu.as_ref() != Some(&menu) {
+                        if let Err(e) = refresh_tray_menu(&handle, &menu) {
+                            eprintln!("Failed to rebuild tray menu: {}", e);
+                        }
+                        tray_menu = Some(menu);
+                    }
+
+                    // Manage Overlay Windows
+                    // Breaks start with a notification only and escalate to the
+                    // overlays, then to fullscreen, while ignored. The windows are
+                    // only touched when that state changes.
+                    let monitors =
+                        if status.active_break.is_some() { monitor_areas(&handle) } else { vec![] };
+                    // Closed overlays stay listed until their `Destroyed` event
+                    let overlays: Vec<_> = handle
+                        .webview_windows()
+                        .into_iter()
+                        .filter(|(label, _)| is_overlay_label(label))
+                        .collect();
+                    let windows = OverlayWindows {
+                        hidden_externally: overlay_manager.is_visible()
+                            && overlays.iter().any(|(label, window)| {
+                                overlay_index(label).is_some_and(|index| index < monitors.len())
+                                    && !window.is_visible().unwrap_or(true)
+                            }),
+                        closing: !overlay_manager.is_visible() && !overlays.is_empty(),
+                    };
+                    let commands =
+                        overlay_manager.update(&status, &monitors, payload, focus_policy, windows);
+                    for command in commands {
+                        if let Err(e) =
+                            apply_overlay_command(&handle, &overlay_manager, &monitors, command)
+                        {
+                            eprintln!("Failed to update overlays ({:?}): {}", command, e);
                         }
                     }
                 }
@@ -277,18 +334,268 @@ pub fn run() {
             commands::update_settings,
             commands::get_settings,
             commands::get_statistics,
-            commands::record_break_taken,
             commands::record_break_postponed,
             commands::reset_break,
+            commands::postpone_break,
+            commands::break_action,
+            commands::override_break,
             commands::set_mode,
             commands::trigger_break
         ])
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-                window.hide().unwrap();
                 api.prevent_close();
+
+                // A locked break keeps its overlay up until the duration has elapsed
+                if is_overlay_label(window.label()) {
+                    let state = window.state::<AppState>();
+                    if state.timer_service.lock().unwrap().is_locked() {
+                        return;
+                    }
+                }
+                window.hide().unwrap();
             }
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+/// Connected monitors, primary first, in the order overlay windows are numbered.
+fn monitor_areas(handle: &AppHandle) -> Vec<MonitorArea> {
+    let mut monitors = handle.available_monitors().unwrap_or_default();
+    if let Ok(Some(primary)) = handle.primary_monitor() {
+        if let Some(index) = monitors.iter().position(|m| m.position() == primary.position()) {
+            monitors.swap(0, index);
+        }
+    }
+    monitors
+        .iter()
+        .map(|m| MonitorArea {
+            x: m.position().x,
+            y: m.position().y,
+            width: m.size().width,
+            height: m.size().height,
+            scale_factor: m.scale_factor(),
+        })
+        .collect()
+}
+
+/// Applies one overlay command to the overlay windows, creating one window per
+/// monitor on `Show` and destroying them all on `Hide`.
+fn apply_overlay_command(
+    handle: &AppHandle,
+    manager: &OverlayManager,
+    monitors: &[MonitorArea],
+    command: OverlayCommand,
+) -> tauri::Result<()> {
+    let overlays =
+        || handle.webview_windows().into_iter().filter(|(label, _)| is_overlay_label(label));
+
+    match command {
+        OverlayCommand::Payload(payload) => handle.emit("break-payload", payload)?,
+        OverlayCommand::Show { fullscreen, focus } => {
+            for (index, monitor) in monitors.iter().enumerate() {
+                let window = match handle.get_webview_window(&overlay_label(index)) {
+                    Some(window) => window,
+                    None => build_overlay(handle, index, manager.payload())?,
+                };
+
+                let (x, y, width, height) = monitor.overlay_bounds(fullscreen);
+                window.set_fullscreen(false)?;
+                window.set_position(PhysicalPosition::new(x, y))?;
+                window.set_size(PhysicalSize::new(width, height))?;
+                window.set_fullscreen(fullscreen)?;
+                window.show()?;
+                // Focus goes to the overlay on the primary monitor
+                if focus && index == 0 {
+                    window.set_focus()?;
+                }
+            }
+            // Left over from a layout with more monitors
+            for (label, window) in overlays() {
+                if overlay_index(&label).is_some_and(|index| index >= monitors.len()) {
+                    window.destroy()?;
+                }
+            }
+        }
+        OverlayCommand::SetFullscreen(fullscreen) => {
+            for (label, window) in overlays() {
+                if let Some(monitor) = overlay_index(&label).and_then(|index| monitors.get(index)) {
+                    let (x, y, width, height) = monitor.overlay_bounds(fullscreen);
+                    window.set_fullscreen(false)?;
+                    window.set_position(PhysicalPosition::new(x, y))?;
+                    window.set_size(PhysicalSize::new(width, height))?;
+                }
+                window.set_fullscreen(fullscreen)?;
+            }
+        }
+        OverlayCommand::Focus => {
+            if let Some(window) = handle.get_webview_window(&overlay_label(0)) {
+                window.set_focus()?;
+            }
+        }
+        OverlayCommand::Hide => {
+            // Destroyed asynchronously: the manager holds back the next `Show`
+            // until the labels are free again
+            for (_, window) in overlays() {
+                window.destroy()?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Creates the hidden overlay window for monitor `index`.
+fn build_overlay(
+    handle: &AppHandle,
+    index: usize,
+    payload: Option<BreakPayload>,
+) -> tauri::Result<WebviewWindow> {
+    // Windows still loading get the payload once the page is ready
+    WebviewWindowBuilder::new(handle, overlay_label(index), WebviewUrl::default())
+        .title("RSI Break")
+        .decorations(false)
+        .transparent(true)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .resizable(false)
+        .visible(false)
+        .on_page_load(move |window, load| {
+            if let (PageLoadEvent::Finished, Some(payload)) = (load.event(), payload) {
+                let _ = window.emit("break-payload", payload);
+            }
+        })
+        .build()
+}
+
+/// Builds the tray menu, taking the break, suspend and mode entries from `state`.
+fn build_tray_menu(app: &AppHandle, state: &TrayMenu) -> tauri::Result<Menu<Wry>> {
+    use timer::OperationMode;
+
+    // TODO: Use IconMenuItem once we have distinct icons. For now using standard MenuItem with placeholders where icons would be.
+    let menu = Menu::new(app)?;
+    menu.append(&MenuItem::with_id(app, "show", "Show RSI Assistant", true, None::<&str>)?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+
+    for entry in &state.breaks {
+        menu.append(&MenuItem::with_id(app, &entry.id, &entry.label, true, None::<&str>)?)?;
+    }
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+
+    menu.append(&MenuItem::with_id(app, "exercises", "Exercises", true, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, "statistics", "Statistics", true, None::<&str>)?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+
+    // Operation Mode submenu
+    let mode_item = |id: &str, label: &str, mode: OperationMode| {
+        CheckMenuItem::with_id(app, id, label, true, state.mode == mode, None::<&str>)
+    };
+    let mode_submenu = Submenu::with_items(
+        app,
+        "Mode",
+        true,
+        &[
+            &mode_item("mode_normal", "Normal", OperationMode::Normal)?,
+            &mode_item("mode_quiet", "Quiet", OperationMode::Quiet)?,
+            &mode_item("mode_suspended", "Suspended", OperationMode::Suspended)?,
+        ],
+    )?;
+    menu.append(&mode_submenu)?;
+
+    let suspend_submenu = Submenu::new(app, "Suspend for…", true)?;
+    for entry in &state.suspend {
+        suspend_submenu.append(&MenuItem::with_id(
+            app,
+            &entry.id,
+            &entry.label,
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    menu.append(&suspend_submenu)?;
+
+    menu.append(&MenuItem::with_id(app, "preferences", "Preferences", true, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, "about", "About", true, None::<&str>)?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?)?;
+    Ok(menu)
+}
+
+impl commands::StateProvider for AppHandle {
+    fn with_state<R>(&self, f: impl FnOnce(&AppState) -> R) -> R {
+        f(&self.state::<AppState>())
+    }
+}
+
+fn refresh_tray_menu(app: &AppHandle, state: &TrayMenu) -> tauri::Result<()> {
+    if let Some(tray) = app.tray_by_id("tray") {
+        tray.set_menu(Some(build_tray_menu(app, state)?))?;
+    }
+    Ok(())
+}
+
+/// Replaces the registered global shortcuts with the bindings in `config`.
+pub(crate) fn register_shortcuts(app: &AppHandle, config: &ShortcutConfig) -> Result<(), String> {
+    let bindings = parse_bindings(config)?;
+    let global_shortcut = app.global_shortcut();
+    global_shortcut.unregister_all().map_err(|e| e.to_string())?;
+
+    for (shortcut, action) in bindings {
+        global_shortcut
+            .on_shortcut(shortcut, move |app, _, event| {
+                if event.state() == ShortcutState::Pressed {
+                    run_shortcut(app, action);
+                }
+            })
+            .map_err(|e| format!("Failed to register shortcut {}: {}", shortcut, e))?;
+    }
+    Ok(())
+}
+
+fn run_shortcut(app: &AppHandle, action: ShortcutAction) {
+    let state = app.state::<AppState>();
+    let result = match action {
+        ShortcutAction::TakeMicrobreak => {
+            state.apply_break_action(BreakType::Micro, BreakAction::TakeNow)
+        }
+        ShortcutAction::TakeRestBreak => {
+            state.apply_break_action(BreakType::Rest, BreakAction::TakeNow)
+        }
+        ShortcutAction::PostponeBreak => {
+            let current = state.timer_service.lock().unwrap().current_break();
+            match current {
+                Some(break_type) => state.apply_break_action(break_type, BreakAction::Postpone),
+                None => Err("No break to postpone".to_string()),
+            }
+        }
+        ShortcutAction::CycleMode => {
+            state.timer_service.lock().unwrap().cycle_mode();
+            Ok(())
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("Failed to run shortcut ({:?}): {}", action, e);
+    }
+}
+
+/// Carries out a CLI command through the matching Tauri command and replies to
+/// the launch that sent it.
+fn run_cli(app: &AppHandle, request: CliRequest) {
+    let result = match request.command {
+        CliCommand::Break { break_type } => {
+            commands::trigger_break(app.state(), break_type).map(|_| String::new())
+        }
+        CliCommand::Mode { mode } => commands::set_mode(app.state(), mode).map(|_| String::new()),
+        CliCommand::Status { format, .. } => {
+            Ok(cli::format_status(&commands::get_timer_state(app.state()), format.as_ref()))
+        }
+    };
+    match request.reply {
+        Some(reply) => cli::send_reply(&reply, &result),
+        None => {
+            if let Err(e) = result {
+                eprintln!("Failed to run CLI command: {}", e);
+            }
+        }
+    }
+}
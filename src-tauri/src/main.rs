@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // Commands such as `rsi-assistant status` are handed to the running instance
+    if let Some(code) = rsi_assistant_lib::cli::forward_from_args() {
+        std::process::exit(code);
+    }
     rsi_assistant_lib::run()
 }
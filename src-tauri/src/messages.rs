@@ -0,0 +1,237 @@
+use crate::timer::{BreakType, TimerService};
+use chrono::{DateTime, Local};
+
+/// Notification templates for one locale. Placeholders are written as `{name}`.
+struct Catalog {
+    micro_title: &'static str,
+    rest_title: &'static str,
+    micro_overdue_title: &'static str,
+    rest_overdue_title: &'static str,
+    ignored_title: &'static str,  // {title}, {count}
+    micro_body: &'static str,     // {duration}
+    rest_body: &'static str,      // {duration}
+    next_rest: &'static str,      // {time}
+    postpones_left: &'static str, // {count}
+    one_postpone_left: &'static str,
+    no_postpones_left: &'static str,
+}
+
+const EN: Catalog = Catalog {
+    micro_title: "Microbreak Time",
+    rest_title: "Rest Break Time",
+    micro_overdue_title: "Microbreak Overdue",
+    rest_overdue_title: "Rest Break Overdue",
+    ignored_title: "{title}! ({count} reminders ignored)",
+    micro_body: "Take a short {duration} break!",
+    rest_body: "Time for a {duration} rest.",
+    next_rest: "Next rest break at {time}.",
+    postpones_left: "{count} postpones left.",
+    one_postpone_left: "1 postpone left.",
+    no_postpones_left: "No postpones left.",
+};
+
+const ES: Catalog = Catalog {
+    micro_title: "Hora de una micropausa",
+    rest_title: "Hora de descansar",
+    micro_overdue_title: "Micropausa atrasada",
+    rest_overdue_title: "Descanso atrasado",
+    ignored_title: "¡{title}! ({count} recordatorios ignorados)",
+    micro_body: "¡Tómate una pausa corta de {duration}!",
+    rest_body: "Es hora de un descanso de {duration}.",
+    next_rest: "Próximo descanso a las {time}.",
+    postpones_left: "Quedan {count} aplazamientos.",
+    one_postpone_left: "Queda 1 aplazamiento.",
+    no_postpones_left: "No quedan aplazamientos.",
+};
+
+const DE: Catalog = Catalog {
+    micro_title: "Zeit für eine Mikropause",
+    rest_title: "Zeit für eine Ruhepause",
+    micro_overdue_title: "Mikropause überfällig",
+    rest_overdue_title: "Ruhepause überfällig",
+    ignored_title: "{title}! ({count} Erinnerungen ignoriert)",
+    micro_body: "Mach eine kurze Pause von {duration}!",
+    rest_body: "Zeit für eine Ruhepause von {duration}.",
+    next_rest: "Nächste Ruhepause um {time}.",
+    postpones_left: "Noch {count} Verschiebungen möglich.",
+    one_postpone_left: "Noch 1 Verschiebung möglich.",
+    no_postpones_left: "Keine Verschiebungen mehr möglich.",
+};
+
+/// What a break prompt needs to say, taken from the timer at prompt time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prompt {
+    pub break_type: BreakType,
+    pub repeat: u32,
+    pub duration: u64,
+    pub next_rest: Option<DateTime<Local>>,
+    pub postpones_left: Option<u32>,
+}
+
+impl Prompt {
+    pub fn from_service(service: &TimerService, break_type: BreakType, repeat: u32) -> Self {
+        // Only a microbreak prompt has a rest break still ahead of it
+        let next_rest = match break_type {
+            BreakType::Micro => service
+                .seconds_until(BreakType::Rest)
+                .map(|secs| Local::now() + chrono::Duration::seconds(secs as i64)),
+            BreakType::Rest => None,
+        };
+
+        Self {
+            break_type,
+            repeat,
+            duration: service.break_duration(break_type),
+            next_rest,
+            postpones_left: service.postpones_left(break_type),
+        }
+    }
+}
+
+pub struct Messages {
+    catalog: &'static Catalog,
+}
+
+impl Messages {
+    /// Messages for the configured locale, falling back to the system locale.
+    pub fn new(locale: Option<&str>) -> Self {
+        match locale {
+            Some(locale) => Self::for_locale(locale),
+            None => Self::for_locale(&system_locale()),
+        }
+    }
+
+    /// Accepts POSIX (`es_ES.UTF-8`) and BCP 47 (`es-ES`) tags. Unknown
+    /// languages fall back to English.
+    pub fn for_locale(locale: &str) -> Self {
+        let language = locale.split(['_', '-', '.', '@']).next().unwrap_or_default();
+        let catalog = match language.to_ascii_lowercase().as_str() {
+            "es" => &ES,
+            "de" => &DE,
+            _ => &EN,
+        };
+        Self { catalog }
+    }
+
+    pub fn title(&self, prompt: &Prompt) -> String {
+        let c = self.catalog;
+        let (title, overdue) = match prompt.break_type {
+            BreakType::Micro => (c.micro_title, c.micro_overdue_title),
+            BreakType::Rest => (c.rest_title, c.rest_overdue_title),
+        };
+        // Repeated prompts escalate in urgency
+        match prompt.repeat {
+            0 => title.to_string(),
+            1 | 2 => overdue.to_string(),
+            count => fill(
+                c.ignored_title,
+                &[("title", overdue.to_string()), ("count", count.to_string())],
+            ),
+        }
+    }
+
+    pub fn body(&self, prompt: &Prompt) -> String {
+        let c = self.catalog;
+        let template = match prompt.break_type {
+            BreakType::Micro => c.micro_body,
+            BreakType::Rest => c.rest_body,
+        };
+        let mut lines = vec![fill(template, &[("duration", format_duration(prompt.duration))])];
+
+        if let Some(time) = prompt.next_rest {
+            lines.push(fill(c.next_rest, &[("time", time.format("%H:%M").to_string())]));
+        }
+        match prompt.postpones_left {
+            None => {}
+            Some(0) => lines.push(c.no_postpones_left.to_string()),
+            Some(1) => lines.push(c.one_postpone_left.to_string()),
+            Some(count) => lines.push(fill(c.postpones_left, &[("count", count.to_string())])),
+        }
+
+        lines.join(" ")
+    }
+}
+
+/// Locale from the environment, as set by the desktop session.
+fn system_locale() -> String {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+        .unwrap_or_else(|| "en".to_string())
+}
+
+fn fill(template: &str, args: &[(&str, String)]) -> String {
+    args.iter().fold(template.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+fn format_duration(seconds: u64) -> String {
+    match (seconds / 60, seconds % 60) {
+        (0, secs) => format!("{}s", secs),
+        (mins, 0) => format!("{} min", mins),
+        (mins, secs) => format!("{} min {}s", mins, secs),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn prompt(break_type: BreakType, duration: u64) -> Prompt {
+        Prompt { break_type, repeat: 0, duration, next_rest: None, postpones_left: None }
+    }
+
+    #[test]
+    fn test_body_uses_configured_duration() {
+        let messages = Messages::for_locale("en_US.UTF-8");
+        assert_eq!(messages.body(&prompt(BreakType::Micro, 20)), "Take a short 20s break!");
+        assert_eq!(messages.body(&prompt(BreakType::Rest, 600)), "Time for a 10 min rest.");
+    }
+
+    #[test]
+    fn test_body_with_next_rest_and_postpones() {
+        let messages = Messages::for_locale("en");
+        let mut prompt = prompt(BreakType::Micro, 30);
+        prompt.next_rest = Some(Local.with_ymd_and_hms(2024, 1, 1, 14, 5, 0).unwrap());
+        prompt.postpones_left = Some(1);
+
+        assert_eq!(
+            messages.body(&prompt),
+            "Take a short 30s break! Next rest break at 14:05. 1 postpone left."
+        );
+    }
+
+    #[test]
+    fn test_localized_and_escalating_titles() {
+        let messages = Messages::for_locale("es-ES");
+        let mut prompt = prompt(BreakType::Rest, 90);
+        assert_eq!(messages.title(&prompt), "Hora de descansar");
+        assert_eq!(messages.body(&prompt), "Es hora de un descanso de 1 min 30s.");
+
+        prompt.repeat = 3;
+        assert_eq!(messages.title(&prompt), "¡Descanso atrasado! (3 recordatorios ignorados)");
+    }
+
+    #[test]
+    fn test_unknown_locale_falls_back_to_english() {
+        let messages = Messages::for_locale("xx_XX");
+        assert_eq!(messages.title(&prompt(BreakType::Micro, 30)), "Microbreak Time");
+    }
+
+    #[test]
+    fn test_prompt_from_service() {
+        let mut config = crate::timer::BreakConfig::default();
+        config.microbreak_duration = 45;
+        config.max_postpones = 2;
+        let service = TimerService::new(config);
+
+        let prompt = Prompt::from_service(&service, BreakType::Micro, 0);
+        assert_eq!(prompt.duration, 45);
+        assert_eq!(prompt.postpones_left, Some(2));
+        assert!(prompt.next_rest.is_some());
+        assert!(Prompt::from_service(&service, BreakType::Rest, 0).next_rest.is_none());
+    }
+}
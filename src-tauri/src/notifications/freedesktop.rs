@@ -0,0 +1,285 @@
+use super::{action_key, action_label, parse_action_key};
+use crate::timer::{BreakAction, BreakType};
+use futures_util::StreamExt;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use zbus::zvariant::Value;
+use zbus::Connection;
+
+const APP_NAME: &str = "RSI Assistant";
+// A notification server that does not answer by then is given up on
+const NOTIFY_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[zbus::proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications"
+)]
+trait Notifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: HashMap<&str, Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+
+    #[zbus(signal)]
+    fn action_invoked(&self, id: u32, action_key: String) -> zbus::Result<()>;
+}
+
+/// Urgency hint from the notification spec.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Urgency {
+    Normal = 1,
+    Critical = 2,
+}
+
+/// Sends break prompts with action buttons and reports which action was chosen.
+pub struct ActionNotifier {
+    proxy: NotificationsProxy<'static>,
+    // Notification id -> break it prompts for
+    pending: Mutex<HashMap<u32, BreakType>>,
+}
+
+impl ActionNotifier {
+    pub async fn connect() -> zbus::Result<Self> {
+        Self::with_connection(&Connection::session().await?).await
+    }
+
+    pub async fn with_connection(connection: &Connection) -> zbus::Result<Self> {
+        let proxy = NotificationsProxy::new(connection).await?;
+        Ok(Self { proxy, pending: Mutex::new(HashMap::new()) })
+    }
+
+    /// Shows a prompt for `break_type`, replacing any earlier prompt for the same break.
+    /// Fails if the server does not answer within `NOTIFY_TIMEOUT`.
+    pub async fn notify(
+        &self,
+        break_type: BreakType,
+        summary: &str,
+        body: &str,
+        actions: &[BreakAction],
+        urgency: Urgency,
+    ) -> zbus::Result<u32> {
+        let actions: Vec<&str> =
+            actions.iter().flat_map(|&action| [action_key(action), action_label(action)]).collect();
+        let hints = HashMap::from([("urgency", Value::U8(urgency as u8))]);
+
+        let replaces_id = {
+            let pending = self.pending.lock().unwrap();
+            pending.iter().find(|(_, &pending)| pending == break_type).map_or(0, |(&id, _)| id)
+        };
+
+        let shown =
+            self.proxy.notify(APP_NAME, replaces_id, "", summary, body, &actions, hints, -1);
+        let id = tokio::time::timeout(NOTIFY_TIMEOUT, shown)
+            .await
+            .map_err(|_| zbus::Error::Failure("Notification server did not answer".into()))??;
+
+        let mut pending = self.pending.lock().unwrap();
+        pending.remove(&replaces_id);
+        pending.insert(id, break_type);
+        Ok(id)
+    }
+
+    /// Waits for action invocations and hands each one to `on_action`. Runs until
+    /// the connection closes.
+    pub async fn listen(
+        &self,
+        mut on_action: impl FnMut(BreakType, BreakAction),
+    ) -> zbus::Result<()> {
+        let mut invoked = self.proxy.receive_action_invoked().await?;
+        while let Some(signal) = invoked.next().await {
+            let args = signal.args()?;
+            let break_type = self.pending.lock().unwrap().remove(&args.id);
+            if let (Some(break_type), Some(action)) =
+                (break_type, parse_action_key(&args.action_key))
+            {
+                on_action(break_type, action);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::net::UnixStream;
+    use tokio::sync::mpsc;
+    use zbus::object_server::SignalEmitter;
+
+    struct Received {
+        id: u32,
+        summary: String,
+        actions: Vec<String>,
+        urgency: u8,
+    }
+
+    // Stand-in for a notification daemon, served over a private connection
+    struct StandInDaemon {
+        next_id: u32,
+        received: Arc<Mutex<Vec<Received>>>,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.Notifications")]
+    impl StandInDaemon {
+        #[allow(clippy::too_many_arguments)]
+        fn notify(
+            &mut self,
+            _app_name: &str,
+            replaces_id: u32,
+            _app_icon: &str,
+            summary: &str,
+            _body: &str,
+            actions: Vec<String>,
+            hints: HashMap<String, zbus::zvariant::OwnedValue>,
+            _expire_timeout: i32,
+        ) -> u32 {
+            let id = if replaces_id == 0 {
+                self.next_id += 1;
+                self.next_id
+            } else {
+                replaces_id
+            };
+            let urgency = hints.get("urgency").and_then(|v| u8::try_from(v).ok()).unwrap_or(1);
+            self.received.lock().unwrap().push(Received {
+                id,
+                summary: summary.to_string(),
+                actions,
+                urgency,
+            });
+            id
+        }
+
+        #[zbus(signal)]
+        async fn action_invoked(
+            emitter: &SignalEmitter<'_>,
+            id: u32,
+            action_key: &str,
+        ) -> zbus::Result<()>;
+    }
+
+    struct UnresponsiveDaemon;
+
+    #[zbus::interface(name = "org.freedesktop.Notifications")]
+    impl UnresponsiveDaemon {
+        #[allow(clippy::too_many_arguments)]
+        async fn notify(
+            &self,
+            _app_name: &str,
+            _replaces_id: u32,
+            _app_icon: &str,
+            _summary: &str,
+            _body: &str,
+            _actions: Vec<String>,
+            _hints: HashMap<String, zbus::zvariant::OwnedValue>,
+            _expire_timeout: i32,
+        ) -> u32 {
+            std::future::pending().await
+        }
+    }
+
+    async fn connect_to_stand_in<I: zbus::object_server::Interface>(
+        daemon: I,
+    ) -> (Connection, Connection) {
+        let (server_stream, client_stream) = UnixStream::pair().unwrap();
+        let guid = zbus::Guid::generate();
+        let server = zbus::connection::Builder::unix_stream(server_stream)
+            .server(guid)
+            .unwrap()
+            .p2p()
+            .serve_at("/org/freedesktop/Notifications", daemon)
+            .unwrap()
+            .build();
+        let client = zbus::connection::Builder::unix_stream(client_stream).p2p().build();
+        let (server, client) = tokio::join!(server, client);
+        (server.unwrap(), client.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_notify_sends_actions_and_routes_invocations() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let daemon = StandInDaemon { next_id: 0, received: received.clone() };
+        let (server, client) = connect_to_stand_in(daemon).await;
+
+        let notifier = Arc::new(ActionNotifier::with_connection(&client).await.unwrap());
+        let id = notifier
+            .notify(
+                BreakType::Rest,
+                "Rest Break Time",
+                "Time for a longer rest.",
+                &[BreakAction::TakeNow, BreakAction::Postpone],
+                Urgency::Critical,
+            )
+            .await
+            .unwrap();
+
+        {
+            let received = received.lock().unwrap();
+            assert_eq!(received[0].id, id);
+            assert_eq!(received[0].summary, "Rest Break Time");
+            assert_eq!(received[0].actions, ["take-now", "Take now", "postpone", "Postpone"]);
+            assert_eq!(received[0].urgency, 2);
+        }
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let listener = notifier.clone();
+        tokio::spawn(async move {
+            let _ = listener
+                .listen(move |break_type, action| tx.send((break_type, action)).unwrap())
+                .await;
+        });
+        // Let the listener subscribe before the daemon emits
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let emitter = SignalEmitter::new(&server, "/org/freedesktop/Notifications").unwrap();
+        StandInDaemon::action_invoked(&emitter, id, "postpone").await.unwrap();
+
+        let invoked = tokio::time::timeout(std::time::Duration::from_secs(2), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(invoked, (BreakType::Rest, BreakAction::Postpone));
+    }
+
+    #[tokio::test]
+    async fn test_repeated_prompt_replaces_previous() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let daemon = StandInDaemon { next_id: 0, received: received.clone() };
+        let (_server, client) = connect_to_stand_in(daemon).await;
+
+        let notifier = ActionNotifier::with_connection(&client).await.unwrap();
+        let actions = [BreakAction::TakeNow];
+        let first =
+            notifier.notify(BreakType::Micro, "a", "", &actions, Urgency::Normal).await.unwrap();
+        let second =
+            notifier.notify(BreakType::Micro, "b", "", &actions, Urgency::Normal).await.unwrap();
+        let other =
+            notifier.notify(BreakType::Rest, "c", "", &actions, Urgency::Normal).await.unwrap();
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+        assert_eq!(received.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_notify_times_out() {
+        let (_server, client) = connect_to_stand_in(UnresponsiveDaemon).await;
+
+        let notifier = ActionNotifier::with_connection(&client).await.unwrap();
+        let started = std::time::Instant::now();
+        let shown = notifier.notify(BreakType::Micro, "a", "", &[], Urgency::Normal).await;
+        assert!(shown.is_err());
+        assert!(started.elapsed() < NOTIFY_TIMEOUT * 2);
+        assert!(notifier.pending.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,50 @@
+// Action helpers are only needed where actionable notifications are supported
+#![cfg_attr(not(target_os = "linux"), allow(dead_code))]
+
+#[cfg(target_os = "linux")]
+pub mod freedesktop;
+
+use crate::timer::BreakAction;
+
+// Action keys sent to the notification server
+pub const ACTION_TAKE_NOW: &str = "take-now";
+pub const ACTION_POSTPONE: &str = "postpone";
+pub const ACTION_SKIP: &str = "skip";
+
+pub fn action_key(action: BreakAction) -> &'static str {
+    match action {
+        BreakAction::TakeNow => ACTION_TAKE_NOW,
+        BreakAction::Postpone => ACTION_POSTPONE,
+        BreakAction::Skip => ACTION_SKIP,
+    }
+}
+
+pub fn action_label(action: BreakAction) -> &'static str {
+    match action {
+        BreakAction::TakeNow => "Take now",
+        BreakAction::Postpone => "Postpone",
+        BreakAction::Skip => "Skip",
+    }
+}
+
+pub fn parse_action_key(key: &str) -> Option<BreakAction> {
+    match key {
+        ACTION_TAKE_NOW => Some(BreakAction::TakeNow),
+        ACTION_POSTPONE => Some(BreakAction::Postpone),
+        ACTION_SKIP => Some(BreakAction::Skip),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_keys_round_trip() {
+        for action in [BreakAction::TakeNow, BreakAction::Postpone, BreakAction::Skip] {
+            assert_eq!(parse_action_key(action_key(action)), Some(action));
+        }
+        assert_eq!(parse_action_key("default"), None);
+    }
+}
@@ -0,0 +1,392 @@
+use crate::timer::{
+    BreakAction, BreakStrictness, BreakType, EscalationLevel, FocusPolicy, TimerService,
+    TimerStatus,
+};
+use serde::Serialize;
+
+/// Sent to the overlay as the `break-payload` event whenever the break it shows changes.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakPayload {
+    pub break_type: BreakType,
+    pub duration: u64,
+    pub strictness: BreakStrictness,
+    // Buttons the overlay offers, as `TimerService::available_actions` allows
+    pub can_postpone: bool,
+    pub can_skip: bool,
+}
+
+impl BreakPayload {
+    /// Payload for the running break, if any.
+    pub fn from_service(service: &TimerService) -> Option<Self> {
+        let countdown = service.active_break?;
+        let actions = service.available_actions(countdown.break_type);
+        Some(Self {
+            break_type: countdown.break_type,
+            duration: countdown.duration,
+            strictness: service.strictness(countdown.break_type),
+            can_postpone: actions.contains(&BreakAction::Postpone),
+            can_skip: actions.contains(&BreakAction::Skip),
+        })
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.strictness == BreakStrictness::Locked
+    }
+}
+
+// Overlay windows are labelled `overlay-<n>`, one per monitor, with the
+// primary monitor first
+const OVERLAY_LABEL_PREFIX: &str = "overlay-";
+// Logical size of a windowed (not fullscreen) overlay
+const OVERLAY_WIDTH: f64 = 800.0;
+const OVERLAY_HEIGHT: f64 = 600.0;
+
+pub fn overlay_label(index: usize) -> String {
+    format!("{}{}", OVERLAY_LABEL_PREFIX, index)
+}
+
+/// Monitor index of an overlay window, `None` for any other window.
+pub fn overlay_index(label: &str) -> Option<usize> {
+    label.strip_prefix(OVERLAY_LABEL_PREFIX)?.parse().ok()
+}
+
+pub fn is_overlay_label(label: &str) -> bool {
+    overlay_index(label).is_some()
+}
+
+/// A monitor's position and size in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonitorArea {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+}
+
+impl MonitorArea {
+    /// Physical `(x, y, width, height)` of the overlay on this monitor: the whole
+    /// monitor when fullscreen, otherwise a centered window.
+    pub fn overlay_bounds(&self, fullscreen: bool) -> (i32, i32, u32, u32) {
+        if fullscreen {
+            return (self.x, self.y, self.width, self.height);
+        }
+        let width = ((OVERLAY_WIDTH * self.scale_factor) as u32).min(self.width);
+        let height = ((OVERLAY_HEIGHT * self.scale_factor) as u32).min(self.height);
+        let x = self.x + ((self.width - width) / 2) as i32;
+        let y = self.y + ((self.height - height) / 2) as i32;
+        (x, y, width, height)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OverlayCommand {
+    Payload(BreakPayload),
+    Show { fullscreen: bool, focus: bool },
+    SetFullscreen(bool),
+    Focus,
+    Hide,
+}
+
+/// State of the overlay windows as seen from outside the manager.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OverlayWindows {
+    // An overlay was hidden without going through the manager, e.g. by the frontend
+    pub hidden_externally: bool,
+    // Overlays closed by an earlier `Hide` are not destroyed yet
+    pub closing: bool,
+}
+
+/// Tracks what the overlay windows currently show so that they are only touched
+/// on transitions instead of on every tick.
+///
+/// `Show` means one overlay window per monitor is built and shown, and `Hide`
+/// means they are all closed. Tauri destroys windows asynchronously, so a
+/// `Show` waits until the windows closed before it are gone.
+#[derive(Debug, Default)]
+pub struct OverlayManager {
+    visible: bool,
+    fullscreen: bool,
+    payload: Option<BreakPayload>,
+    // Break and level the overlays were last shown for
+    shown_for: Option<(BreakType, EscalationLevel)>,
+    // Monitor layout the overlays were created for
+    monitors: Vec<MonitorArea>,
+    // A `Show` is held back until closed overlays are destroyed
+    waiting: bool,
+}
+
+impl OverlayManager {
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Payload for overlays that are still loading and missed the last `Payload`.
+    pub fn payload(&self) -> Option<BreakPayload> {
+        self.payload
+    }
+
+    /// Works out the window changes needed to match `status` on `monitors`.
+    /// `payload` describes the running break, as `BreakPayload::from_service`.
+    ///
+    /// When an overlay was hidden externally, the remaining overlays are closed
+    /// and stay closed until the break changes or escalates, unless the break
+    /// is locked.
+    pub fn update(
+        &mut self,
+        status: &TimerStatus,
+        monitors: &[MonitorArea],
+        payload: Option<BreakPayload>,
+        policy: FocusPolicy,
+        windows: OverlayWindows,
+    ) -> Vec<OverlayCommand> {
+        let mut commands = Vec::new();
+
+        if windows.hidden_externally && self.visible {
+            commands.push(OverlayCommand::Hide);
+            self.visible = false;
+        }
+
+        let (countdown, payload) = match (status.active_break, payload) {
+            (Some(countdown), Some(payload))
+                if countdown.escalation >= EscalationLevel::Overlay =>
+            {
+                (countdown, payload)
+            }
+            _ => {
+                if self.visible {
+                    commands.push(OverlayCommand::Hide);
+                }
+                *self = Self::default();
+                return commands;
+            }
+        };
+
+        let locked = payload.is_locked();
+        if self.payload != Some(payload) {
+            self.payload = Some(payload);
+            commands.push(OverlayCommand::Payload(payload));
+        }
+
+        let fullscreen = countdown.escalation == EscalationLevel::Fullscreen;
+        let target = (countdown.break_type, countdown.escalation);
+        let changed = self.shown_for != Some(target);
+
+        // Overlays are rebuilt for a new monitor layout
+        let rebuild = self.visible && self.monitors != monitors;
+        if rebuild {
+            commands.push(OverlayCommand::Hide);
+            self.visible = false;
+        }
+
+        if !self.visible {
+            if !(changed || locked || rebuild || self.waiting) {
+                return commands;
+            }
+            // Overlays closed on this tick or before are destroyed first
+            if windows.closing || commands.contains(&OverlayCommand::Hide) {
+                self.waiting = true;
+                return commands;
+            }
+            commands.push(OverlayCommand::Show { fullscreen, focus: policy != FocusPolicy::Never });
+            self.visible = true;
+            self.waiting = false;
+            self.fullscreen = fullscreen;
+            self.shown_for = Some(target);
+            self.monitors = monitors.to_vec();
+        } else if changed {
+            if fullscreen != self.fullscreen {
+                commands.push(OverlayCommand::SetFullscreen(fullscreen));
+                self.fullscreen = fullscreen;
+            }
+            if policy == FocusPolicy::OnEscalation {
+                commands.push(OverlayCommand::Focus);
+            }
+            self.shown_for = Some(target);
+        }
+
+        commands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::{BreakConfig, TimerService};
+
+    const MONITOR: MonitorArea =
+        MonitorArea { x: 0, y: 0, width: 1920, height: 1080, scale_factor: 1.0 };
+    const OPEN: OverlayWindows = OverlayWindows { hidden_externally: false, closing: false };
+    const HIDDEN: OverlayWindows = OverlayWindows { hidden_externally: true, closing: false };
+    const CLOSING: OverlayWindows = OverlayWindows { hidden_externally: false, closing: true };
+
+    type Shown = (TimerStatus, Option<BreakPayload>);
+
+    fn status_at(level: Option<EscalationLevel>) -> Shown {
+        break_at(level, BreakStrictness::Skippable)
+    }
+
+    fn break_at(level: Option<EscalationLevel>, strictness: BreakStrictness) -> Shown {
+        let mut config = BreakConfig::default();
+        config.microbreak_strictness = strictness;
+        let mut service = TimerService::new(config);
+        if let Some(level) = level {
+            service.take_break_now(BreakType::Micro);
+            service.active_break.as_mut().unwrap().escalation = level;
+        }
+        (service.get_status(), BreakPayload::from_service(&service))
+    }
+
+    #[test]
+    fn test_shows_once_and_hides_on_transition() {
+        let mut manager = OverlayManager::default();
+        let policy = FocusPolicy::OnShow;
+        let monitors = [MONITOR];
+
+        let (idle, none) = status_at(None);
+        assert!(manager.update(&idle, &monitors, none, policy, OPEN).is_empty());
+
+        let (status, payload) = status_at(Some(EscalationLevel::Overlay));
+        let commands = manager.update(&status, &monitors, payload, policy, OPEN);
+        assert!(
+            matches!(commands[0], OverlayCommand::Payload(p) if p.break_type == BreakType::Micro)
+        );
+        assert_eq!(commands[1], OverlayCommand::Show { fullscreen: false, focus: true });
+
+        // Nothing to do while the break is unchanged
+        assert!(manager.update(&status, &monitors, payload, policy, OPEN).is_empty());
+
+        assert_eq!(manager.update(&idle, &monitors, none, policy, OPEN), [OverlayCommand::Hide]);
+        assert!(!manager.is_visible());
+    }
+
+    #[test]
+    fn test_payload_follows_strictness() {
+        let level = Some(EscalationLevel::Overlay);
+
+        let (_, skippable) = break_at(level, BreakStrictness::Skippable);
+        let skippable = skippable.unwrap();
+        assert!(skippable.can_postpone && skippable.can_skip && !skippable.is_locked());
+
+        let (_, postponable) = break_at(level, BreakStrictness::Postponable);
+        let postponable = postponable.unwrap();
+        assert!(postponable.can_postpone && !postponable.can_skip);
+
+        let (_, locked) = break_at(level, BreakStrictness::Locked);
+        let locked = locked.unwrap();
+        assert!(!locked.can_postpone && !locked.can_skip && locked.is_locked());
+
+        let json = serde_json::to_value(postponable).unwrap();
+        assert_eq!(json["strictness"], "postponable");
+        assert_eq!(json["canPostpone"], true);
+        assert_eq!(json["canSkip"], false);
+    }
+
+    #[test]
+    fn test_escalation_and_focus_policy() {
+        let (overlay, payload) = status_at(Some(EscalationLevel::Overlay));
+        let (fullscreen, _) = status_at(Some(EscalationLevel::Fullscreen));
+        let monitors = [MONITOR];
+
+        let mut manager = OverlayManager::default();
+        manager.update(&overlay, &monitors, payload, FocusPolicy::OnEscalation, OPEN);
+        assert_eq!(
+            manager.update(&fullscreen, &monitors, payload, FocusPolicy::OnEscalation, OPEN),
+            [OverlayCommand::SetFullscreen(true), OverlayCommand::Focus]
+        );
+
+        let mut manager = OverlayManager::default();
+        manager.update(&overlay, &monitors, payload, FocusPolicy::Never, OPEN);
+        assert_eq!(
+            manager.update(&fullscreen, &monitors, payload, FocusPolicy::Never, OPEN),
+            [OverlayCommand::SetFullscreen(true)]
+        );
+    }
+
+    #[test]
+    fn test_external_hide_respected_unless_locked() {
+        let (status, payload) = status_at(Some(EscalationLevel::Overlay));
+        let policy = FocusPolicy::OnShow;
+        let monitors = [MONITOR];
+
+        let mut manager = OverlayManager::default();
+        manager.update(&status, &monitors, payload, policy, OPEN);
+        assert_eq!(
+            manager.update(&status, &monitors, payload, policy, HIDDEN),
+            [OverlayCommand::Hide]
+        );
+        assert!(manager.update(&status, &monitors, payload, policy, OPEN).is_empty());
+        assert!(!manager.is_visible());
+
+        let (status, locked) = break_at(Some(EscalationLevel::Overlay), BreakStrictness::Locked);
+        let mut manager = OverlayManager::default();
+        manager.update(&status, &monitors, locked, policy, OPEN);
+        assert_eq!(
+            manager.update(&status, &monitors, locked, policy, HIDDEN),
+            [OverlayCommand::Hide]
+        );
+        // Shown again once the closed windows are destroyed
+        assert!(manager.update(&status, &monitors, locked, policy, CLOSING).is_empty());
+        assert_eq!(
+            manager.update(&status, &monitors, locked, policy, OPEN),
+            [OverlayCommand::Show { fullscreen: false, focus: true }]
+        );
+    }
+
+    #[test]
+    fn test_overlays_rebuilt_when_monitor_layout_changes() {
+        let (status, payload) = status_at(Some(EscalationLevel::Fullscreen));
+        let policy = FocusPolicy::Never;
+        let second = MonitorArea { x: 1920, ..MONITOR };
+
+        let mut manager = OverlayManager::default();
+        manager.update(&status, &[MONITOR], payload, policy, OPEN);
+        let monitors = [MONITOR, second];
+        assert_eq!(
+            manager.update(&status, &monitors, payload, policy, OPEN),
+            [OverlayCommand::Hide]
+        );
+        assert_eq!(
+            manager.update(&status, &monitors, payload, policy, OPEN),
+            [OverlayCommand::Show { fullscreen: true, focus: false }]
+        );
+        assert!(manager.update(&status, &monitors, payload, policy, OPEN).is_empty());
+    }
+
+    #[test]
+    fn test_next_break_waits_for_closed_overlays() {
+        let (idle, none) = status_at(None);
+        let (status, payload) = status_at(Some(EscalationLevel::Overlay));
+        let policy = FocusPolicy::OnShow;
+        let monitors = [MONITOR];
+
+        let mut manager = OverlayManager::default();
+        manager.update(&status, &monitors, payload, policy, OPEN);
+        assert_eq!(manager.update(&idle, &monitors, none, policy, OPEN), [OverlayCommand::Hide]);
+
+        // The next break comes while the old windows are still being destroyed
+        let commands = manager.update(&status, &monitors, payload, policy, CLOSING);
+        assert!(matches!(commands[..], [OverlayCommand::Payload(_)]));
+        assert!(!manager.is_visible());
+        assert!(manager.update(&status, &monitors, payload, policy, CLOSING).is_empty());
+        assert_eq!(
+            manager.update(&status, &monitors, payload, policy, OPEN),
+            [OverlayCommand::Show { fullscreen: false, focus: true }]
+        );
+    }
+
+    #[test]
+    fn test_overlay_bounds() {
+        let monitor = MonitorArea { x: 1920, y: 0, width: 2560, height: 1440, scale_factor: 2.0 };
+        assert_eq!(monitor.overlay_bounds(true), (1920, 0, 2560, 1440));
+        // 800x600 logical at 2x, centered on the monitor
+        assert_eq!(monitor.overlay_bounds(false), (2400, 120, 1600, 1200));
+
+        assert_eq!(overlay_label(1), "overlay-1");
+        assert_eq!(overlay_index("overlay-1"), Some(1));
+        assert!(is_overlay_label("overlay-0"));
+        assert!(!is_overlay_label("overlay"));
+        assert!(!is_overlay_label("main"));
+    }
+}
@@ -0,0 +1,260 @@
+//! Settings and statistics files shared by the app and the headless daemon.
+
+use crate::stats::StatsStore;
+use crate::timer::BreakConfig;
+use std::fs::{File, TryLockError};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+// Must match `identifier` in tauri.conf.json, which names the app data directory
+const APP_IDENTIFIER: &str = "com.rsi-assistant.app";
+
+// Written by the frontend through the store plugin
+const SETTINGS_FILE: &str = "settings.json";
+const SETTINGS_KEY: &str = "break_config";
+
+const STATS_FILE: &str = "stats.json";
+
+// Held by the running app or daemon, which each keep statistics in memory
+const LOCK_FILE: &str = "instance.lock";
+
+/// How often running instances write statistics back, in seconds.
+pub const SAVE_INTERVAL_SECS: u64 = 60;
+
+/// The app data directory, as Tauri resolves it, for use outside of Tauri.
+pub fn data_dir() -> Option<PathBuf> {
+    let base = if cfg!(target_os = "macos") {
+        home_dir().map(|home| home.join("Library/Application Support"))
+    } else if cfg!(windows) {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| home_dir().map(|home| home.join(".local/share")))
+    };
+    base.map(|base| base.join(APP_IDENTIFIER))
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+pub fn settings_path(dir: &Path) -> PathBuf {
+    dir.join(SETTINGS_FILE)
+}
+
+/// Settings saved from the Settings page, or `None` if there are none yet.
+pub fn load_settings(dir: &Path) -> Result<Option<BreakConfig>, String> {
+    read_settings(&settings_path(dir))
+}
+
+fn read_settings(path: &Path) -> Result<Option<BreakConfig>, String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read settings: {}", e)),
+    };
+    let mut store: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(&text).map_err(|e| format!("Invalid settings file: {}", e))?;
+    store
+        .remove(SETTINGS_KEY)
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| format!("Invalid settings: {}", e))
+}
+
+/// Notices when the settings file is saved, by the app or by hand.
+pub struct SettingsWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl SettingsWatcher {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, modified: None }
+    }
+
+    /// The saved settings, if the file changed since the last poll.
+    pub fn poll(&mut self) -> Option<Result<BreakConfig, String>> {
+        let modified = std::fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        read_settings(&self.path).transpose()
+    }
+}
+
+/// Exclusive use of the data directory, released when dropped.
+pub struct DataDirLock {
+    _file: File,
+}
+
+/// Locks the data directory so the app and the daemon never overwrite each
+/// other's statistics. Fails if another instance holds it.
+pub fn lock_data_dir(dir: &Path) -> Result<DataDirLock, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    let file = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join(LOCK_FILE))
+        .map_err(|e| format!("Failed to open lock file: {}", e))?;
+    match file.try_lock() {
+        Ok(()) => Ok(DataDirLock { _file: file }),
+        Err(TryLockError::WouldBlock) => {
+            Err("Another instance of the app or the daemon is already running".to_string())
+        }
+        Err(TryLockError::Error(e)) => Err(format!("Failed to lock data dir: {}", e)),
+    }
+}
+
+/// Saved statistics, starting empty if there are none yet.
+pub fn load_stats(dir: &Path) -> Result<StatsStore, String> {
+    match std::fs::read_to_string(dir.join(STATS_FILE)) {
+        Ok(text) => serde_json::from_str(&text).map_err(|e| format!("Invalid stats file: {}", e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(StatsStore::default()),
+        Err(e) => Err(format!("Failed to read stats: {}", e)),
+    }
+}
+
+pub fn save_stats(dir: &Path, stats: &StatsStore) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(stats).map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+
+    // Written aside and renamed so a crash never leaves a truncated file
+    let path = dir.join(STATS_FILE);
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, text)
+        .and_then(|_| std::fs::rename(&temp, &path))
+        .map_err(|e| format!("Failed to save stats: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::OperationMode;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("rsi-assistant-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_stats_round_trip() {
+        let dir = temp_dir("stats");
+        assert!(load_stats(&dir).unwrap().stats.is_empty());
+
+        let mut stats = StatsStore::default();
+        stats.get_or_create_today().micro_prompts = 3;
+        save_stats(&dir, &stats).unwrap();
+
+        let loaded = load_stats(&dir).unwrap();
+        assert_eq!(loaded.get_last_n_days(1)[0].micro_prompts, 3);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_settings_from_store_file() {
+        let dir = temp_dir("settings");
+        assert_eq!(load_settings(&dir).unwrap().map(|c| c.rest_duration), None);
+
+        // As the Settings page saves it through the store plugin
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            settings_path(&dir),
+            r#"{
+  "break_config": {
+    "microbreakInterval": 1800,
+    "microbreakDuration": 30,
+    "microbreakEnabled": true,
+    "restInterval": 5400,
+    "restDuration": 900,
+    "restEnabled": true,
+    "dailyLimit": 28800,
+    "dailyEnabled": false,
+    "warningDuration": 30,
+    "mode": "quiet",
+    "shortcuts": {
+      "takeMicrobreak": "CommandOrControl+Alt+M",
+      "takeRestBreak": null,
+      "postponeBreak": null,
+      "cycleMode": null
+    }
+  }
+}"#,
+        )
+        .unwrap();
+        let config = load_settings(&dir).unwrap().unwrap();
+        assert_eq!(config.rest_duration, 900);
+        assert!(!config.daily_enabled);
+        assert_eq!(config.mode, OperationMode::Quiet);
+        assert_eq!(config.shortcuts.take_microbreak.as_deref(), Some("CommandOrControl+Alt+M"));
+        assert_eq!(config.coalesce_window, BreakConfig::default().coalesce_window);
+
+        std::fs::write(settings_path(&dir), "not json").unwrap();
+        assert!(load_settings(&dir).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_settings_saved_by_older_frontend() {
+        let dir = temp_dir("legacy-settings");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            settings_path(&dir),
+            r#"{"break_config": {"microbreak_interval": 1200, "rest_duration": 900, "daily_limit": 14400, "mode": "Suspended"}}"#,
+        )
+        .unwrap();
+        let config = load_settings(&dir).unwrap().unwrap();
+        assert_eq!(config.microbreak_interval, 1200);
+        assert_eq!(config.rest_duration, 900);
+        assert_eq!(config.daily_limit, 14400);
+        assert_eq!(config.mode, OperationMode::Suspended);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_one_instance_saves_stats_at_a_time() {
+        let dir = temp_dir("lock");
+        let app = lock_data_dir(&dir).unwrap();
+        let mut app_stats = load_stats(&dir).unwrap();
+        app_stats.get_or_create_today().micro_prompts = 2;
+        save_stats(&dir, &app_stats).unwrap();
+
+        // The daemon cannot start, so it never saves its own, older copy
+        assert!(lock_data_dir(&dir).is_err());
+
+        drop(app);
+        let _daemon = lock_data_dir(&dir).unwrap();
+        let mut daemon_stats = load_stats(&dir).unwrap();
+        daemon_stats.get_or_create_today().rest_prompts = 1;
+        save_stats(&dir, &daemon_stats).unwrap();
+
+        let today = load_stats(&dir).unwrap().get_last_n_days(1)[0].clone();
+        assert_eq!((today.micro_prompts, today.rest_prompts), (2, 1));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_settings_watcher_reports_changes_once() {
+        let dir = temp_dir("watcher");
+        let mut watcher = SettingsWatcher::new(settings_path(&dir));
+        assert!(watcher.poll().is_none());
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(settings_path(&dir), r#"{"break_config": {"restDuration": 900}}"#).unwrap();
+        assert_eq!(watcher.poll().unwrap().unwrap().rest_duration, 900);
+        assert!(watcher.poll().is_none());
+
+        // Rewritten with a later modification time
+        std::fs::write(settings_path(&dir), r#"{"break_config": {"restDuration": 600}}"#).unwrap();
+        let file = std::fs::File::options().write(true).open(settings_path(&dir)).unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(5)).unwrap();
+        assert_eq!(watcher.poll().unwrap().unwrap().rest_duration, 600);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,239 @@
+//! The per-second work shared by the app's background loop and the headless
+//! daemon: settings, idle and session state, meetings and busy contexts, the
+//! timer tick, hooks, the D-Bus and socket APIs, saving statistics and break
+//! prompts. Each host adds its own windows, tray or shutdown handling around it.
+
+use crate::calendar::MeetingWatcher;
+use crate::commands::{StateProvider, Tick};
+use crate::hooks::Hooks;
+use crate::idle::{DeviceQueryIdleDetector, IdleDetector};
+use crate::messages::Messages;
+#[cfg(target_os = "linux")]
+use crate::notifications::freedesktop::{ActionNotifier, Urgency};
+use crate::persistence::{self, SettingsWatcher};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Shows a prompt's title and body where actionable notifications are not
+/// available.
+pub type FallbackNotifier = Arc<dyn Fn(String, String) + Send + Sync>;
+
+pub struct TickPipeline<P: StateProvider> {
+    provider: P,
+    data_dir: PathBuf,
+    // Settings file to follow, for hosts whose settings are not pushed to them
+    settings: Option<SettingsWatcher>,
+    idle_detector: DeviceQueryIdleDetector,
+    meetings: MeetingWatcher,
+    hooks: Hooks,
+    fallback: FallbackNotifier,
+    ticks: u64,
+    #[cfg(target_os = "linux")]
+    action_notifier: Option<Arc<ActionNotifier>>,
+    #[cfg(target_os = "linux")]
+    dbus_service: Option<crate::dbus::DbusService<P>>,
+    #[cfg(target_os = "linux")]
+    logind_monitor: Option<crate::idle::logind::LogindMonitor>,
+    #[cfg(target_os = "linux")]
+    busy_monitor: Option<crate::busy::BusyMonitor>,
+    #[cfg(unix)]
+    rpc_api: crate::rpc::RpcApi,
+}
+
+impl<P: StateProvider> TickPipeline<P> {
+    /// Connects to the session services, going without any that are
+    /// unavailable. Must be called from within the Tokio runtime.
+    pub async fn connect(
+        provider: P,
+        data_dir: PathBuf,
+        settings: Option<SettingsWatcher>,
+        fallback: FallbackNotifier,
+    ) -> Self {
+        let input = provider.with_state(|state| state.timer_service.lock().unwrap().config.input);
+
+        // Actionable notifications where the notification server supports them
+        #[cfg(target_os = "linux")]
+        let action_notifier = match ActionNotifier::connect().await {
+            Ok(notifier) => {
+                let notifier = Arc::new(notifier);
+                let listener = notifier.clone();
+                let action_provider = provider.clone();
+                tokio::spawn(async move {
+                    let result = listener
+                        .listen(|break_type, action| {
+                            let result = action_provider
+                                .with_state(|state| state.apply_break_action(break_type, action));
+                            if let Err(e) = result {
+                                eprintln!("Failed to apply notification action: {}", e);
+                            }
+                        })
+                        .await;
+                    if let Err(e) = result {
+                        eprintln!("Notification action listener stopped: {}", e);
+                    }
+                });
+                Some(notifier)
+            }
+            Err(e) => {
+                eprintln!("Notification actions unavailable: {}", e);
+                None
+            }
+        };
+
+        #[cfg(target_os = "linux")]
+        let dbus_service = match crate::dbus::DbusService::connect(provider.clone()).await {
+            Ok(service) => Some(service),
+            Err(e) => {
+                eprintln!("D-Bus service unavailable: {}", e);
+                None
+            }
+        };
+
+        #[cfg(target_os = "linux")]
+        let logind_monitor = match crate::idle::logind::LogindMonitor::connect().await {
+            Ok(monitor) => Some(monitor),
+            Err(e) => {
+                eprintln!("Lock screen detection unavailable: {}", e);
+                None
+            }
+        };
+
+        #[cfg(target_os = "linux")]
+        let busy_monitor = match crate::busy::BusyDetector::connect().await {
+            Ok(detector) => Some(crate::busy::BusyMonitor::new(detector)),
+            Err(e) => {
+                eprintln!("Busy detection unavailable: {}", e);
+                None
+            }
+        };
+
+        Self {
+            provider,
+            data_dir,
+            settings,
+            idle_detector: DeviceQueryIdleDetector::new(input),
+            meetings: MeetingWatcher::default(),
+            hooks: Hooks::default(),
+            fallback,
+            ticks: 0,
+            #[cfg(target_os = "linux")]
+            action_notifier,
+            #[cfg(target_os = "linux")]
+            dbus_service,
+            #[cfg(target_os = "linux")]
+            logind_monitor,
+            #[cfg(target_os = "linux")]
+            busy_monitor,
+            #[cfg(unix)]
+            rpc_api: crate::rpc::RpcApi::new(crate::rpc::socket_path()),
+        }
+    }
+
+    /// Runs one second of the background loop and returns what happened, for
+    /// the host to update its own views.
+    pub async fn tick(&mut self) -> Tick {
+        let provider = self.provider.clone();
+
+        if let Some(config) = self.settings.as_mut().and_then(SettingsWatcher::poll) {
+            match config {
+                Ok(config) => provider
+                    .with_state(|state| state.timer_service.lock().unwrap().update_config(config)),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+
+        let (input, calendar) = provider.with_state(|state| {
+            let service = state.timer_service.lock().unwrap();
+            (service.config.input, service.config.calendar.clone())
+        });
+        self.idle_detector.set_config(input);
+        let idle_seconds = self.idle_detector.get_seconds_since_last_input();
+        // A locked or sleeping session is idle whatever input is seen
+        #[cfg(target_os = "linux")]
+        let idle_seconds = match &self.logind_monitor {
+            Some(monitor)
+                if provider.with_state(|state| {
+                    monitor.apply(&mut state.timer_service.lock().unwrap())
+                }) =>
+            {
+                u64::MAX
+            }
+            _ => idle_seconds,
+        };
+
+        let now = chrono::Local::now().naive_local();
+        let in_meeting = self.meetings.in_meeting(calendar.as_deref(), now);
+        provider.with_state(|state| state.timer_service.lock().unwrap().set_in_meeting(in_meeting));
+
+        // Checked in the background while no break is running
+        #[cfg(target_os = "linux")]
+        if let Some(monitor) = &mut self.busy_monitor {
+            provider.with_state(|state| {
+                let mut service = state.timer_service.lock().unwrap();
+                if service.active_break.is_none() {
+                    if self.ticks.is_multiple_of(crate::busy::CHECK_INTERVAL_SECS) {
+                        monitor.start_check(service.config.defer.clone());
+                    }
+                    service.set_busy(monitor.is_busy());
+                }
+            });
+        }
+
+        let mut tick = provider.with_state(|state| state.tick(idle_seconds));
+
+        let (hook_config, api_socket, messages) = provider.with_state(|state| {
+            let service = state.timer_service.lock().unwrap();
+            (
+                service.config.hooks.clone(),
+                service.config.api_socket,
+                Messages::new(service.config.locale.as_deref()),
+            )
+        });
+        self.hooks.update(&hook_config, &tick.status, &tick.events);
+
+        #[cfg(target_os = "linux")]
+        if let Some(service) = &mut self.dbus_service {
+            service.update(&tick.status, &tick.events).await;
+        }
+
+        #[cfg(unix)]
+        self.rpc_api.update(api_socket, &provider, &tick.status);
+        #[cfg(not(unix))]
+        let _ = api_socket;
+
+        self.ticks += 1;
+        if self.ticks.is_multiple_of(persistence::SAVE_INTERVAL_SECS) {
+            if let Err(e) = provider.with_state(|state| state.save_stats(&self.data_dir)) {
+                eprintln!("{}", e);
+            }
+        }
+
+        for (prompt, actions) in std::mem::take(&mut tick.prompts) {
+            let title = messages.title(&prompt);
+            let body = messages.body(&prompt);
+            let fallback = self.fallback.clone();
+            #[cfg(target_os = "linux")]
+            let notifier = self.action_notifier.clone();
+
+            // A slow notification server must not hold up the tick
+            tokio::spawn(async move {
+                #[cfg(target_os = "linux")]
+                if let Some(notifier) = notifier {
+                    let urgency =
+                        if prompt.repeat == 0 { Urgency::Normal } else { Urgency::Critical };
+                    match notifier.notify(prompt.break_type, &title, &body, &actions, urgency).await
+                    {
+                        Ok(_) => return,
+                        Err(e) => eprintln!("Failed to show actionable notification: {}", e),
+                    }
+                }
+                #[cfg(not(target_os = "linux"))]
+                let _ = (prompt, actions);
+
+                fallback(title, body);
+            });
+        }
+
+        tick
+    }
+}
@@ -0,0 +1,512 @@
+//! Opt-in JSON-RPC 2.0 API on a Unix socket, one message per line, so that
+//! scripts, editor plugins and status bars can query and drive the timer.
+//!
+//! Methods: `status`, `trigger`, `postpone`, `setMode`, `stats` and
+//! `subscribe`, after which every timer update is sent as a `status`
+//! notification. Given a status bar `format`, `status` returns the formatted
+//! text and `subscribe` sends a `statusLine` notification when it changes.
+
+use crate::commands::{AppState, StateProvider};
+use crate::statusbar::{self, StatusFormat};
+use crate::timer::{BreakAction, BreakType, OperationMode, TimerStatus};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::broadcast;
+use tokio::task::{JoinHandle, JoinSet};
+
+const SOCKET_DIR: &str = "rsi-assistant";
+const SOCKET_NAME: &str = "rsi-assistant.sock";
+
+// Standard JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+// Rejected by the timer, e.g. postponing a locked break
+const COMMAND_FAILED: i64 = -32000;
+
+const DEFAULT_STATS_DAYS: usize = 7;
+
+/// The app's private directory in the user's runtime directory, or in the temp
+/// directory. Create it with `private_dir` before use.
+pub fn runtime_dir() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .unwrap_or_else(std::env::temp_dir)
+        .join(SOCKET_DIR)
+}
+
+/// Where the API listens.
+pub fn socket_path() -> PathBuf {
+    runtime_dir().join(SOCKET_NAME)
+}
+
+/// The API as driven from the background loop: serves while the `apiSocket`
+/// setting is on and streams each status to subscribers.
+pub struct RpcApi {
+    path: PathBuf,
+    updates: broadcast::Sender<TimerStatus>,
+    server: Option<JoinHandle<()>>,
+    enabled: bool,
+}
+
+impl RpcApi {
+    pub fn new(path: PathBuf) -> Self {
+        let (updates, _) = broadcast::channel(16);
+        Self { path, updates, server: None, enabled: false }
+    }
+
+    /// Starts or stops the server when the setting changes. Must be called from
+    /// within the Tokio runtime.
+    pub fn update<P: StateProvider>(&mut self, enabled: bool, provider: &P, status: &TimerStatus) {
+        if enabled != self.enabled {
+            self.enabled = enabled;
+            if enabled {
+                match serve(&self.path, provider.clone(), self.updates.clone()) {
+                    Ok(server) => self.server = Some(server),
+                    Err(e) => eprintln!("Failed to start API socket: {}", e),
+                }
+            } else {
+                self.stop();
+            }
+        }
+
+        // Fails only when nobody is subscribed
+        let _ = self.updates.send(*status);
+    }
+
+    fn stop(&mut self) {
+        if let Some(server) = self.server.take() {
+            server.abort();
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+impl Drop for RpcApi {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn serve<P: StateProvider>(
+    path: &Path,
+    provider: P,
+    updates: broadcast::Sender<TimerStatus>,
+) -> Result<JoinHandle<()>, String> {
+    // Bound inside a directory only the user can enter, so the socket is never
+    // reachable by others, even before its own permissions are set
+    if let Some(dir) = path.parent() {
+        private_dir(dir)?;
+    }
+
+    // A socket file left by a crash is replaced, one still being served is not
+    if std::os::unix::net::UnixStream::connect(path).is_ok() {
+        return Err(format!("{} is in use by another instance", path.display()));
+    }
+    let _ = std::fs::remove_file(path);
+
+    let listener = UnixListener::bind(path).map_err(|e| e.to_string())?;
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let private = std::fs::Permissions::from_mode(0o600);
+        std::fs::set_permissions(path, private).map_err(|e| e.to_string())?;
+    }
+
+    // Connections belong to the server task: aborting it drops the set, which
+    // closes them too
+    Ok(tokio::spawn(async move {
+        let mut connections = JoinSet::new();
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        let connection = serve_connection(stream, provider.clone(), updates.clone());
+                        connections.spawn(connection);
+                    }
+                    Err(e) => {
+                        eprintln!("API socket stopped: {}", e);
+                        break;
+                    }
+                },
+                // Reaps closed connections
+                Some(_) = connections.join_next(), if !connections.is_empty() => {}
+            }
+        }
+    }))
+}
+
+/// Creates `dir` as accessible only by the user, or checks that it already is.
+pub fn private_dir(dir: &Path) -> Result<(), String> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    match std::fs::DirBuilder::new().mode(0o700).create(dir) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+        Err(e) => return Err(format!("Failed to create {}: {}", dir.display(), e)),
+    }
+    let metadata = std::fs::symlink_metadata(dir).map_err(|e| e.to_string())?;
+    if !metadata.is_dir() || metadata.permissions().mode() & 0o077 != 0 {
+        return Err(format!("{} is accessible by other users", dir.display()));
+    }
+    Ok(())
+}
+
+async fn serve_connection<P: StateProvider>(
+    stream: UnixStream,
+    provider: P,
+    updates: broadcast::Sender<TimerStatus>,
+) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let mut receiver: Option<broadcast::Receiver<TimerStatus>> = None;
+    let mut subscription = None;
+
+    loop {
+        let message = tokio::select! {
+            line = lines.next_line() => {
+                let Ok(Some(line)) = line else { break };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let (response, subscribed) = provider.with_state(|state| handle_line(state, &line));
+                if let Some(subscribed) = subscribed {
+                    receiver.get_or_insert_with(|| updates.subscribe());
+                    subscription = Some(subscribed);
+                }
+                match response {
+                    Some(response) => response,
+                    None => continue,
+                }
+            }
+            status = next_update(&mut receiver) => match (status, &mut subscription) {
+                (Ok(status), Some(Subscription::Lines { format, last })) => {
+                    let line = statusbar::render(&status, format);
+                    if line == *last {
+                        continue;
+                    }
+                    *last = line.clone();
+                    json!({ "jsonrpc": "2.0", "method": "statusLine", "params": { "line": line } })
+                }
+                (Ok(status), _) => json!({ "jsonrpc": "2.0", "method": "status", "params": status }),
+                // A slow reader only misses intermediate updates
+                (Err(broadcast::error::RecvError::Lagged(_)), _) => continue,
+                (Err(broadcast::error::RecvError::Closed), _) => break,
+            },
+        };
+
+        let mut line = message.to_string();
+        line.push('\n');
+        if writer.write_all(line.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn next_update(
+    subscription: &mut Option<broadcast::Receiver<TimerStatus>>,
+) -> Result<TimerStatus, broadcast::error::RecvError> {
+    match subscription {
+        Some(receiver) => receiver.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// What a connection receives after `subscribe`.
+#[derive(Debug, PartialEq)]
+pub enum Subscription {
+    Status,
+    Lines { format: StatusFormat, last: String }, // sent only when changed
+}
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Option<Value>, // absent for notifications, which get no response
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BreakParams {
+    break_type: BreakType,
+}
+
+#[derive(Deserialize)]
+struct ModeParams {
+    mode: OperationMode,
+}
+
+#[derive(Deserialize, Default)]
+struct FormatParams {
+    format: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct StatsParams {
+    days: Option<usize>,
+}
+
+/// Handles one request line. Returns the response, if one is due, and what the
+/// connection should receive from now on if it subscribed.
+pub fn handle_line(state: &AppState, line: &str) -> (Option<Value>, Option<Subscription>) {
+    let request: Request = match serde_json::from_str::<Value>(line) {
+        Err(e) => return (Some(error_response(Value::Null, PARSE_ERROR, e.to_string())), None),
+        Ok(value) => match serde_json::from_value(value) {
+            Ok(request) => request,
+            Err(e) => {
+                return (Some(error_response(Value::Null, INVALID_REQUEST, e.to_string())), None)
+            }
+        },
+    };
+
+    let mut subscription = None;
+    let result = match request.method.as_str() {
+        "subscribe" => subscribe(state, request.params).map(|(result, subscribed)| {
+            subscription = Some(subscribed);
+            result
+        }),
+        method => call(state, method, request.params),
+    };
+    let response = request.id.map(|id| match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_response(id, code, message),
+    });
+    (response, subscription)
+}
+
+fn subscribe(state: &AppState, params: Value) -> Result<(Value, Subscription), (i64, String)> {
+    let status = state.timer_service.lock().unwrap().get_status();
+    match parse_format(params)? {
+        Some(format) => {
+            let line = statusbar::render(&status, &format);
+            Ok((json!(line), Subscription::Lines { format, last: line }))
+        }
+        None => Ok((json!(status), Subscription::Status)),
+    }
+}
+
+fn call(state: &AppState, method: &str, params: Value) -> Result<Value, (i64, String)> {
+    match method {
+        "status" => {
+            let status = state.timer_service.lock().unwrap().get_status();
+            match parse_format(params)? {
+                Some(format) => Ok(json!(statusbar::render(&status, &format))),
+                None => Ok(json!(status)),
+            }
+        }
+        "trigger" => {
+            let BreakParams { break_type } = parse_params(params)?;
+            let mut service = state.timer_service.lock().unwrap();
+            match break_type {
+                BreakType::Micro => service.trigger_microbreak(),
+                BreakType::Rest => service.trigger_rest_break(),
+            }
+            Ok(Value::Null)
+        }
+        "postpone" => {
+            let BreakParams { break_type } = parse_params(params)?;
+            state
+                .apply_break_action(break_type, BreakAction::Postpone)
+                .map_err(|e| (COMMAND_FAILED, e))?;
+            Ok(Value::Null)
+        }
+        "setMode" => {
+            let ModeParams { mode } = parse_params(params)?;
+            state.timer_service.lock().unwrap().set_mode(mode);
+            Ok(Value::Null)
+        }
+        "stats" => {
+            let params: StatsParams =
+                if params.is_null() { StatsParams::default() } else { parse_params(params)? };
+            let days = params.days.unwrap_or(DEFAULT_STATS_DAYS);
+            Ok(json!(state.stats_store.lock().unwrap().get_last_n_days(days)))
+        }
+        _ => Err((METHOD_NOT_FOUND, format!("Unknown method: {}", method))),
+    }
+}
+
+fn parse_format(params: Value) -> Result<Option<StatusFormat>, (i64, String)> {
+    let params: FormatParams =
+        if params.is_null() { FormatParams::default() } else { parse_params(params)? };
+    params.format.map(|format| format.parse().map_err(|e| (INVALID_PARAMS, e))).transpose()
+}
+
+fn parse_params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T, (i64, String)> {
+    serde_json::from_value(params).map_err(|e| (INVALID_PARAMS, e.to_string()))
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::StatsStore;
+    use crate::timer::{BreakConfig, BreakStrictness, TimerService};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    fn app_state(config: BreakConfig) -> AppState {
+        AppState {
+            timer_service: Mutex::new(TimerService::new(config)),
+            stats_store: Mutex::new(StatsStore::default()),
+        }
+    }
+
+    fn request(state: &AppState, line: &str) -> Value {
+        handle_line(state, line).0.unwrap()
+    }
+
+    #[test]
+    fn test_methods_drive_the_timer() {
+        let state = app_state(BreakConfig::default());
+
+        let status = request(&state, r#"{"jsonrpc":"2.0","id":1,"method":"status"}"#);
+        assert_eq!(status["id"], 1);
+        assert_eq!(status["result"]["mode"], "normal");
+
+        let set = request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":"a","method":"setMode","params":{"mode":"quiet"}}"#,
+        );
+        assert_eq!(set, json!({ "jsonrpc": "2.0", "id": "a", "result": null }));
+        assert_eq!(state.timer_service.lock().unwrap().config.mode, OperationMode::Quiet);
+
+        request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":2,"method":"trigger","params":{"breakType":"rest"}}"#,
+        );
+        request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":3,"method":"postpone","params":{"breakType":"rest"}}"#,
+        );
+        let stats =
+            request(&state, r#"{"jsonrpc":"2.0","id":4,"method":"stats","params":{"days":1}}"#);
+        assert_eq!(stats["result"][0]["restPostponed"], 1);
+
+        // Notifications are carried out without a response
+        let (response, _) = handle_line(
+            &state,
+            r#"{"jsonrpc":"2.0","method":"setMode","params":{"mode":"normal"}}"#,
+        );
+        assert!(response.is_none());
+        assert_eq!(state.timer_service.lock().unwrap().config.mode, OperationMode::Normal);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut config = BreakConfig::default();
+        config.rest_strictness = BreakStrictness::Locked;
+        let state = app_state(config);
+
+        let code = |line: &str| request(&state, line)["error"]["code"].clone();
+        assert_eq!(code("{not json"), PARSE_ERROR);
+        assert_eq!(code(r#"{"id":1}"#), INVALID_REQUEST);
+        assert_eq!(code(r#"{"id":1,"method":"reboot"}"#), METHOD_NOT_FOUND);
+        assert_eq!(
+            code(r#"{"id":1,"method":"trigger","params":{"breakType":"long"}}"#),
+            INVALID_PARAMS
+        );
+
+        request(&state, r#"{"id":1,"method":"trigger","params":{"breakType":"rest"}}"#);
+        state.timer_service.lock().unwrap().tick(false);
+        let locked =
+            request(&state, r#"{"id":2,"method":"postpone","params":{"breakType":"rest"}}"#);
+        assert_eq!(locked["error"]["code"], COMMAND_FAILED);
+        assert_eq!(locked["id"], 2);
+    }
+
+    #[test]
+    fn test_status_formats() {
+        let state = app_state(BreakConfig::default());
+
+        let waybar = request(&state, r#"{"id":1,"method":"status","params":{"format":"waybar"}}"#);
+        let json: Value = serde_json::from_str(waybar["result"].as_str().unwrap()).unwrap();
+        assert_eq!(json["text"], "Micro in 3:00");
+
+        let (response, subscription) =
+            handle_line(&state, r#"{"id":2,"method":"subscribe","params":{"format":"{rest}"}}"#);
+        assert_eq!(response.unwrap()["result"], "45:00");
+        assert_eq!(
+            subscription,
+            Some(Subscription::Lines {
+                format: StatusFormat::Template("{rest}".to_string()),
+                last: "45:00".to_string()
+            })
+        );
+
+        let bad = request(&state, r#"{"id":3,"method":"status","params":{"format":"yaml"}}"#);
+        assert_eq!(bad["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_streams_status_over_socket() {
+        let dir = std::env::temp_dir().join(format!("rsi-assistant-rpc-{}", std::process::id()));
+        let path = dir.join(SOCKET_NAME);
+        let state = Arc::new(app_state(BreakConfig::default()));
+        let mut api = RpcApi::new(path.clone());
+        let status = state.timer_service.lock().unwrap().get_status();
+        api.update(true, &state, &status);
+
+        let stream = UnixStream::connect(&path).await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        writer
+            .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"subscribe\"}\n")
+            .await
+            .unwrap();
+
+        let reply: Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(reply["result"]["microTarget"], 180);
+
+        state.timer_service.lock().unwrap().set_mode(OperationMode::Quiet);
+        let status = state.timer_service.lock().unwrap().get_status();
+        api.update(true, &state, &status);
+        let update: Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(update["method"], "status");
+        assert_eq!(update["params"]["mode"], "quiet");
+
+        // Turning the setting off closes the socket and open connections
+        api.update(false, &state, &status);
+        assert!(!path.exists());
+        let closed = tokio::time::timeout(Duration::from_secs(1), lines.next_line()).await;
+        assert!(matches!(closed, Ok(Ok(None)) | Ok(Err(_))));
+        let refused = writer
+            .write_all(
+                b"{\"jsonrpc\":\"2.0\",\"method\":\"setMode\",\"params\":{\"mode\":\"normal\"}}\n",
+            )
+            .await;
+        assert!(refused.is_err());
+        assert_eq!(state.timer_service.lock().unwrap().config.mode, OperationMode::Quiet);
+        std::fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_socket_dir_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("rsi-assistant-dir-{}", std::process::id()));
+        private_dir(&dir).unwrap();
+        let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+
+        // A directory others can enter, e.g. made ahead of time in /tmp, is refused
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o777)).unwrap();
+        assert!(private_dir(&dir).is_err());
+        let state = Arc::new(app_state(BreakConfig::default()));
+        let updates = broadcast::channel(1).0;
+        assert!(serve(&dir.join(SOCKET_NAME), state, updates).is_err());
+        assert!(!dir.join(SOCKET_NAME).exists());
+        std::fs::remove_dir(&dir).unwrap();
+    }
+}
@@ -0,0 +1,111 @@
+use crate::timer::ShortcutConfig;
+use std::collections::HashMap;
+use tauri_plugin_global_shortcut::Shortcut;
+
+/// What a global shortcut does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShortcutAction {
+    TakeMicrobreak,
+    TakeRestBreak,
+    PostponeBreak, // the running break, or else the one that is due
+    CycleMode,
+}
+
+impl ShortcutAction {
+    fn name(self) -> &'static str {
+        match self {
+            ShortcutAction::TakeMicrobreak => "take microbreak",
+            ShortcutAction::TakeRestBreak => "take rest break",
+            ShortcutAction::PostponeBreak => "postpone break",
+            ShortcutAction::CycleMode => "cycle mode",
+        }
+    }
+}
+
+/// Parses the configured bindings, rejecting malformed accelerators and keys
+/// bound to more than one action.
+pub fn parse_bindings(config: &ShortcutConfig) -> Result<Vec<(Shortcut, ShortcutAction)>, String> {
+    let configured = [
+        (&config.take_microbreak, ShortcutAction::TakeMicrobreak),
+        (&config.take_rest_break, ShortcutAction::TakeRestBreak),
+        (&config.postpone_break, ShortcutAction::PostponeBreak),
+        (&config.cycle_mode, ShortcutAction::CycleMode),
+    ];
+
+    let mut bound: HashMap<Shortcut, ShortcutAction> = HashMap::new();
+    let mut bindings = Vec::new();
+    for (accelerator, action) in configured {
+        let Some(accelerator) = accelerator.as_deref().filter(|a| !a.trim().is_empty()) else {
+            continue;
+        };
+        let shortcut: Shortcut = accelerator
+            .parse()
+            .map_err(|e| format!("Invalid shortcut for {}: {}", action.name(), e))?;
+
+        if let Some(other) = bound.insert(shortcut, action) {
+            return Err(format!(
+                "Shortcut {} is bound to both {} and {}",
+                accelerator,
+                other.name(),
+                action.name()
+            ));
+        }
+        bindings.push((shortcut, action));
+    }
+    Ok(bindings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nothing_bound_by_default() {
+        assert!(parse_bindings(&ShortcutConfig::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_suggested_bindings_are_valid() {
+        // As offered on the Settings page
+        let config = ShortcutConfig {
+            take_microbreak: Some("CommandOrControl+Alt+M".to_string()),
+            take_rest_break: Some("CommandOrControl+Alt+R".to_string()),
+            postpone_break: Some("CommandOrControl+Alt+P".to_string()),
+            cycle_mode: Some("CommandOrControl+Alt+S".to_string()),
+        };
+        let bindings = parse_bindings(&config).unwrap();
+        assert_eq!(bindings.len(), 4);
+        assert_eq!(bindings[0].1, ShortcutAction::TakeMicrobreak);
+    }
+
+    #[test]
+    fn test_unbound_actions_are_skipped() {
+        let config = ShortcutConfig {
+            take_microbreak: None,
+            take_rest_break: Some(String::new()),
+            postpone_break: Some("Alt+P".to_string()),
+            cycle_mode: None,
+        };
+        let bindings = parse_bindings(&config).unwrap();
+        assert_eq!(bindings.len(), 1);
+        assert_eq!(bindings[0], ("Alt+P".parse().unwrap(), ShortcutAction::PostponeBreak));
+    }
+
+    #[test]
+    fn test_conflicts_and_invalid_accelerators_rejected() {
+        // Same keys written differently still conflict
+        let mut config = ShortcutConfig::default();
+        config.take_microbreak = Some("Ctrl+Shift+B".to_string());
+        config.take_rest_break = Some("shift+control+b".to_string());
+        assert_eq!(
+            parse_bindings(&config).unwrap_err(),
+            "Shortcut shift+control+b is bound to both take microbreak and take rest break"
+        );
+
+        let mut config = ShortcutConfig::default();
+        config.cycle_mode = Some("Ctrl+Alt+".to_string());
+        assert!(parse_bindings(&config)
+            .unwrap_err()
+            .starts_with("Invalid shortcut for cycle mode"));
+    }
+}
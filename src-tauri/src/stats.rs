@@ -1,6 +1,15 @@
+use crate::timer::{BreakType, TimerEvent};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakOverride {
+    pub time: String, // Format: HH:MM:SS
+    pub break_type: BreakType,
+    pub reason: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DailyStats {
@@ -14,6 +23,10 @@ pub struct DailyStats {
     pub micro_natural_taken: u32,
     pub micro_skipped: u32,
     pub micro_postponed: u32,
+    #[serde(default)]
+    pub micro_interrupted: u32, // input seen during the break
+    #[serde(default)]
+    pub micro_deferred: u32, // held back while busy
 
     // Rest Breaks
     pub rest_prompts: u32,
@@ -22,6 +35,10 @@ pub struct DailyStats {
     pub rest_natural_taken: u32,
     pub rest_skipped: u32,
     pub rest_postponed: u32,
+    #[serde(default)]
+    pub rest_interrupted: u32,
+    #[serde(default)]
+    pub rest_deferred: u32,
 
     // Daily Limit
     pub daily_prompts: u32,
@@ -32,6 +49,10 @@ pub struct DailyStats {
     pub daily_postponed: u32,
 
     pub overdue_seconds: u64,
+
+    // Locked breaks dismissed through the emergency override
+    #[serde(default)]
+    pub overrides: Vec<BreakOverride>,
 }
 
 impl Default for DailyStats {
@@ -46,6 +67,8 @@ impl Default for DailyStats {
             micro_natural_taken: 0,
             micro_skipped: 0,
             micro_postponed: 0,
+            micro_interrupted: 0,
+            micro_deferred: 0,
 
             rest_prompts: 0,
             rest_repeated_prompts: 0,
@@ -53,6 +76,8 @@ impl Default for DailyStats {
             rest_natural_taken: 0,
             rest_skipped: 0,
             rest_postponed: 0,
+            rest_interrupted: 0,
+            rest_deferred: 0,
 
             daily_prompts: 0,
             daily_repeated_prompts: 0,
@@ -62,6 +87,40 @@ impl Default for DailyStats {
             daily_postponed: 0,
 
             overdue_seconds: 0,
+
+            overrides: Vec::new(),
+        }
+    }
+}
+
+impl DailyStats {
+    /// Counts a break lifecycle event reported by the timer.
+    pub fn record_event(&mut self, event: TimerEvent) {
+        match event {
+            TimerEvent::BreakStarted(BreakType::Micro) => self.micro_prompts += 1,
+            TimerEvent::BreakStarted(BreakType::Rest) => self.rest_prompts += 1,
+            TimerEvent::BreakReprompted { break_type: BreakType::Micro, .. } => {
+                self.micro_repeated_prompts += 1
+            }
+            TimerEvent::BreakReprompted { break_type: BreakType::Rest, .. } => {
+                self.rest_repeated_prompts += 1
+            }
+            TimerEvent::BreakInterrupted(BreakType::Micro) => self.micro_interrupted += 1,
+            TimerEvent::BreakInterrupted(BreakType::Rest) => self.rest_interrupted += 1,
+            // Breaks whose countdown ran out were taken as prompted
+            TimerEvent::BreakCompleted(BreakType::Micro) => self.micro_prompted_taken += 1,
+            TimerEvent::BreakCompleted(BreakType::Rest) => self.rest_prompted_taken += 1,
+            TimerEvent::NaturalBreak(BreakType::Micro) => self.micro_natural_taken += 1,
+            TimerEvent::NaturalBreak(BreakType::Rest) => self.rest_natural_taken += 1,
+            TimerEvent::BreakDeferred(BreakType::Micro) => self.micro_deferred += 1,
+            TimerEvent::BreakDeferred(BreakType::Rest) => self.rest_deferred += 1,
+            // Skips are counted where the user skips, with their other actions,
+            // and breaks worked through only as interrupted
+            TimerEvent::BreakEscalated { .. }
+            | TimerEvent::BreakEnded(_)
+            | TimerEvent::BreakWarning(_)
+            | TimerEvent::BreakSkipped(_)
+            | TimerEvent::DailyLimitReached => {}
         }
     }
 }
@@ -99,6 +158,31 @@ mod tests {
         assert_eq!(stats.rest_prompted_taken, 0);
     }
 
+    #[test]
+    fn test_record_event() {
+        let mut stats = DailyStats::default();
+        stats.record_event(TimerEvent::BreakStarted(BreakType::Rest));
+        stats.record_event(TimerEvent::BreakReprompted { break_type: BreakType::Rest, repeat: 1 });
+        stats.record_event(TimerEvent::BreakReprompted { break_type: BreakType::Rest, repeat: 2 });
+        stats.record_event(TimerEvent::BreakCompleted(BreakType::Micro));
+
+        assert_eq!(stats.rest_prompts, 1);
+        assert_eq!(stats.rest_repeated_prompts, 2);
+        assert_eq!(stats.micro_prompted_taken, 1);
+        assert_eq!(stats.micro_repeated_prompts, 0);
+    }
+
+    #[test]
+    fn test_interrupted_break_not_counted_as_taken() {
+        let mut stats = DailyStats::default();
+        stats.record_event(TimerEvent::BreakStarted(BreakType::Rest));
+        stats.record_event(TimerEvent::BreakInterrupted(BreakType::Rest));
+        stats.record_event(TimerEvent::BreakEnded(BreakType::Rest));
+
+        assert_eq!(stats.rest_interrupted, 1);
+        assert_eq!(stats.rest_prompted_taken, 0);
+    }
+
     #[test]
     fn test_stats_store_get_or_create_today() {
         let mut store = StatsStore::default();
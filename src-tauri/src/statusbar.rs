@@ -0,0 +1,253 @@
+//! Status bar output over `TimerStatus`: waybar custom-module JSON, i3blocks
+//! lines and a plain text template.
+
+use crate::timer::{BreakType, OperationMode, TimerStatus};
+use crate::tray::{due, format_clock, tooltip};
+use serde_json::json;
+use std::fmt;
+use std::str::FromStr;
+
+// i3blocks colours for a break needing attention and for a suspended timer,
+// matching the tray icon
+const ALERT_COLOR: &str = "#E53E3E";
+const MUTED_COLOR: &str = "#A0A0A0";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatusFormat {
+    Json,
+    Waybar,
+    I3blocks,
+    Template(String), // any format containing a `{placeholder}`
+}
+
+impl FromStr for StatusFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(StatusFormat::Json),
+            "waybar" => Ok(StatusFormat::Waybar),
+            "i3blocks" => Ok(StatusFormat::I3blocks),
+            _ if s.contains('{') => Ok(StatusFormat::Template(s.to_string())),
+            _ => Err(format!("Unknown status format: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for StatusFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StatusFormat::Json => f.write_str("json"),
+            StatusFormat::Waybar => f.write_str("waybar"),
+            StatusFormat::I3blocks => f.write_str("i3blocks"),
+            StatusFormat::Template(template) => f.write_str(template),
+        }
+    }
+}
+
+/// Renders `status`. Only the i3blocks format spans more than one line: full
+/// text, short text and, when there is one, the colour.
+///
+/// Templates may use `{text}`, `{tooltip}`, `{class}`, `{percentage}`,
+/// `{mode}`, `{micro}` and `{rest}` (time until each break) and `{break}`
+/// (time left in the running break).
+pub fn render(status: &TimerStatus, format: &StatusFormat) -> String {
+    match format {
+        StatusFormat::Json => serde_json::to_string(status).unwrap_or_default(),
+        StatusFormat::Waybar => json!({
+            "text": text(status),
+            "tooltip": tooltip(status),
+            "class": class(status),
+            "percentage": percentage(status),
+        })
+        .to_string(),
+        StatusFormat::I3blocks => {
+            let text = text(status);
+            match color(status) {
+                Some(color) => format!("{}\n{}\n{}", text, text, color),
+                None => format!("{}\n{}", text, text),
+            }
+        }
+        StatusFormat::Template(template) => fill(template, status),
+    }
+}
+
+/// The running break, or else the next one.
+fn text(status: &TimerStatus) -> String {
+    if status.mode == OperationMode::Suspended {
+        return match status.suspend_remaining {
+            Some(remaining) => format!("Suspended {}", format_clock(remaining)),
+            None => "Suspended".to_string(),
+        };
+    }
+    if let Some(countdown) = status.active_break {
+        return format!(
+            "{} break {}",
+            name(countdown.break_type),
+            format_clock(countdown.remaining)
+        );
+    }
+
+    let (break_type, remaining) = next_break(status);
+    if remaining == 0 {
+        format!("{} break due", name(break_type))
+    } else {
+        format!("{} in {}", name(break_type), format_clock(remaining))
+    }
+}
+
+fn class(status: &TimerStatus) -> &'static str {
+    if status.mode == OperationMode::Suspended {
+        "suspended"
+    } else if status.active_break.is_some() {
+        "break"
+    } else if due(status, BreakType::Micro) || due(status, BreakType::Rest) {
+        "due"
+    } else if status.mode == OperationMode::Quiet {
+        "quiet"
+    } else {
+        "normal"
+    }
+}
+
+/// Progress through the running break, or towards the next one.
+fn percentage(status: &TimerStatus) -> u64 {
+    if let Some(countdown) = status.active_break {
+        let elapsed = countdown.duration.saturating_sub(countdown.remaining);
+        return (elapsed * 100).checked_div(countdown.duration).unwrap_or(100);
+    }
+    let (break_type, remaining) = next_break(status);
+    let target = match break_type {
+        BreakType::Micro => status.micro_target,
+        BreakType::Rest => status.rest_target,
+    };
+    ((target - remaining) * 100).checked_div(target).unwrap_or(100)
+}
+
+fn color(status: &TimerStatus) -> Option<&'static str> {
+    match class(status) {
+        "break" | "due" => Some(ALERT_COLOR),
+        "suspended" => Some(MUTED_COLOR),
+        _ => None,
+    }
+}
+
+fn fill(template: &str, status: &TimerStatus) -> String {
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else { break };
+        let key = &rest[start + 1..start + len];
+        match placeholder(key, status) {
+            Some(value) => output.push_str(&value),
+            None => output.push_str(&rest[start..=start + len]), // kept as written
+        }
+        rest = &rest[start + len + 1..];
+    }
+    output.push_str(rest);
+    output
+}
+
+fn placeholder(key: &str, status: &TimerStatus) -> Option<String> {
+    let value = match key {
+        "text" => text(status),
+        "tooltip" => tooltip(status),
+        "class" => class(status).to_string(),
+        "percentage" => percentage(status).to_string(),
+        "mode" => status.mode.name().to_string(),
+        "micro" => format_clock(remaining(status, BreakType::Micro)),
+        "rest" => format_clock(remaining(status, BreakType::Rest)),
+        "break" => status
+            .active_break
+            .map(|countdown| format_clock(countdown.remaining))
+            .unwrap_or_default(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+fn name(break_type: BreakType) -> &'static str {
+    match break_type {
+        BreakType::Micro => "Micro",
+        BreakType::Rest => "Rest",
+    }
+}
+
+/// Seconds of activity until a break is due, 0 once it is.
+fn remaining(status: &TimerStatus, break_type: BreakType) -> u64 {
+    if due(status, break_type) {
+        return 0;
+    }
+    match break_type {
+        BreakType::Micro => status.micro_target - status.micro_active,
+        BreakType::Rest => status.rest_target - status.rest_active,
+    }
+}
+
+/// The break due soonest, the rest break on a tie as it replaces the microbreak.
+fn next_break(status: &TimerStatus) -> (BreakType, u64) {
+    let micro = remaining(status, BreakType::Micro);
+    let rest = remaining(status, BreakType::Rest);
+    if micro < rest {
+        (BreakType::Micro, micro)
+    } else {
+        (BreakType::Rest, rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::{BreakConfig, TimerService};
+
+    fn service() -> TimerService {
+        let mut service = TimerService::new(BreakConfig::default());
+        service.micro_active = 78;
+        service
+    }
+
+    #[test]
+    fn test_parse_formats() {
+        assert_eq!("waybar".parse(), Ok(StatusFormat::Waybar));
+        assert_eq!("{micro}".parse(), Ok(StatusFormat::Template("{micro}".to_string())));
+        assert_eq!("yaml".parse::<StatusFormat>(), Err("Unknown status format: yaml".to_string()));
+        assert_eq!(StatusFormat::I3blocks.to_string(), "i3blocks");
+    }
+
+    #[test]
+    fn test_waybar() {
+        let output = render(&service().get_status(), &StatusFormat::Waybar);
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(json["text"], "Micro in 1:42");
+        assert_eq!(json["tooltip"], "Microbreak in 1:42, rest in 45:00");
+        assert_eq!(json["class"], "normal");
+        assert_eq!(json["percentage"], 43);
+        assert!(!output.contains('\n'));
+    }
+
+    #[test]
+    fn test_i3blocks_colours_breaks() {
+        let mut service = service();
+        assert_eq!(
+            render(&service.get_status(), &StatusFormat::I3blocks),
+            "Micro in 1:42\nMicro in 1:42"
+        );
+
+        service.trigger_rest_break();
+        service.tick(true);
+        assert_eq!(
+            render(&service.get_status(), &StatusFormat::I3blocks),
+            format!("Rest break 10:00\nRest break 10:00\n{}", ALERT_COLOR)
+        );
+    }
+
+    #[test]
+    fn test_template() {
+        let mut service = service();
+        service.set_mode(OperationMode::Quiet);
+        let template =
+            StatusFormat::Template("{micro}/{rest} [{mode}] {unknown} {break}{".to_string());
+        assert_eq!(render(&service.get_status(), &template), "1:42/45:00 [quiet] {unknown} {");
+    }
+}
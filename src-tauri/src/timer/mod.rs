@@ -1,29 +1,258 @@
 use serde::{Deserialize, Serialize};
 
+// Input this many seconds into a break is the user finishing a keystroke or
+// reaching for the mouse, not working through the break
+const BREAK_INPUT_GRACE_SECS: u64 = 3;
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum OperationMode {
+    // Capitalized names were saved by older frontends
+    #[serde(alias = "Normal")]
     Normal,
+    #[serde(alias = "Quiet")]
     Quiet,
+    #[serde(alias = "Suspended")]
     Suspended,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl OperationMode {
+    /// Lowercase name, as serialized.
+    pub fn name(self) -> &'static str {
+        match self {
+            OperationMode::Normal => "normal",
+            OperationMode::Quiet => "quiet",
+            OperationMode::Suspended => "suspended",
+        }
+    }
+}
+
+impl std::str::FromStr for OperationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "normal" => Ok(OperationMode::Normal),
+            "quiet" => Ok(OperationMode::Quiet),
+            "suspended" => Ok(OperationMode::Suspended),
+            _ => Err(format!("Invalid mode: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum BreakType {
+    Micro,
+    Rest,
+}
+
+impl BreakType {
+    /// Lowercase name, as serialized and parsed.
+    pub fn name(self) -> &'static str {
+        match self {
+            BreakType::Micro => "micro",
+            BreakType::Rest => "rest",
+        }
+    }
+}
+
+impl std::str::FromStr for BreakType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "micro" => Ok(BreakType::Micro),
+            "rest" => Ok(BreakType::Rest),
+            _ => Err("Invalid break type".to_string()),
+        }
+    }
+}
+
+/// What the user chose to do with a prompted break.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BreakAction {
+    TakeNow,
+    Postpone,
+    Skip,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum BreakStrictness {
+    Skippable,   // may be skipped or postponed
+    Postponable, // may be postponed, not skipped
+    Locked,      // neither, until the break duration has elapsed
+}
+
+/// What happens to a break countdown when input is detected during the break.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum BreakInputPolicy {
+    Ignore,  // keep counting down
+    Pause,   // hold the countdown while input continues
+    Restart, // start the countdown over
+}
+
+/// Global shortcut bindings in accelerator syntax, e.g. `CommandOrControl+Alt+M`.
+/// `None` leaves an action unbound, as all are until the user binds them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ShortcutConfig {
+    pub take_microbreak: Option<String>,
+    pub take_rest_break: Option<String>,
+    pub postpone_break: Option<String>,
+    pub cycle_mode: Option<String>,
+}
+
+/// Shell commands run on break lifecycle events. Unset events run nothing.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HookConfig {
+    pub warning: Option<String>,
+    pub break_start: Option<String>,
+    pub break_end: Option<String>,
+    pub skipped: Option<String>,
+    pub daily_limit: Option<String>,
+    pub timeout: u64, // seconds before a hook is killed, at least 1
+    pub skip_when_suspended: bool,
+}
+
+impl Default for HookConfig {
+    fn default() -> Self {
+        Self {
+            warning: None,
+            break_start: None,
+            break_end: None,
+            skipped: None,
+            daily_limit: None,
+            timeout: 10,
+            skip_when_suspended: false,
+        }
+    }
+}
+
+/// Holding back break prompts while media plays or an application inhibits
+/// idle, as a video player or presentation does (Linux).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DeferConfig {
+    pub media: bool,      // an MPRIS player is playing
+    pub inhibitors: bool, // idle is inhibited through the session manager
+    pub max_defer: u64,   // seconds a due break may be held back
+}
+
+impl Default for DeferConfig {
+    fn default() -> Self {
+        Self { media: false, inhibitors: true, max_defer: 300 }
+    }
+}
+
+/// What counts as input when polling the mouse.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InputConfig {
+    pub mouse_buttons: bool,   // a held button counts, as when dragging
+    pub jitter_threshold: u32, // pointer moves shorter than this many pixels are ignored
+}
+
+impl Default for InputConfig {
+    fn default() -> Self {
+        Self { mouse_buttons: true, jitter_threshold: 3 }
+    }
+}
+
+/// When the break overlay takes keyboard focus.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum FocusPolicy {
+    Never,
+    OnShow,       // once, when the overlay appears
+    OnEscalation, // when it appears and again each time the break escalates
+}
+
+/// How intrusively a running break is presented, from least to most.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum EscalationLevel {
+    Notification,
+    Overlay,
+    Fullscreen,
+}
+
+/// Seconds of input during a break at which it escalates to the next level.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
+pub struct EscalationConfig {
+    pub overlay_after: u64,
+    pub fullscreen_after: u64,
+}
+
+impl EscalationConfig {
+    pub fn level_at(&self, ignored: u64) -> EscalationLevel {
+        if ignored >= self.fullscreen_after {
+            EscalationLevel::Fullscreen
+        } else if ignored >= self.overlay_after {
+            EscalationLevel::Overlay
+        } else {
+            EscalationLevel::Notification
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+// The snake_case aliases read settings saved by older frontends
+#[serde(rename_all = "camelCase", default)]
 pub struct BreakConfig {
+    #[serde(alias = "microbreak_interval")]
     pub microbreak_interval: u64, // seconds of activity
+    #[serde(alias = "microbreak_duration")]
     pub microbreak_duration: u64, // seconds of idle required
+    #[serde(alias = "microbreak_enabled")]
     pub microbreak_enabled: bool,
+    pub microbreak_strictness: BreakStrictness,
+    pub microbreak_escalation: EscalationConfig,
 
+    #[serde(alias = "rest_interval")]
     pub rest_interval: u64,
+    #[serde(alias = "rest_duration")]
     pub rest_duration: u64,
+    #[serde(alias = "rest_enabled")]
     pub rest_enabled: bool,
+    pub rest_strictness: BreakStrictness,
+    pub rest_escalation: EscalationConfig,
+    // Percent of a full rest credited for idle stretches shorter than
+    // rest_duration (0 disables partial credit)
+    pub rest_partial_credit: u64,
+
+    // A microbreak is folded into the rest break when the rest break is due
+    // within this many seconds
+    pub coalesce_window: u64,
+
+    pub postpone_duration: u64, // seconds a postponed break is pushed back
+    pub max_postpones: u32,     // per break, 0 = unlimited
+    pub break_input_policy: BreakInputPolicy,
+    pub reprompt_interval: u64, // seconds of ignored break before prompting again, 0 = never
+    pub overlay_focus: FocusPolicy,
+    pub shortcuts: ShortcutConfig,
+    pub api_socket: bool,       // serve the JSON-RPC API on a Unix socket
+    pub lock_screen_idle: bool, // count a locked or sleeping session as idle (Linux)
+    pub defer: DeferConfig,
+    pub input: InputConfig,
+    pub calendar: Option<String>, // .ics file or directory whose meetings hold breaks back
+    pub hooks: HookConfig,
 
+    #[serde(alias = "daily_limit")]
     pub daily_limit: u64,
+    #[serde(alias = "daily_enabled")]
     pub daily_enabled: bool,
 
+    #[serde(alias = "warning_duration")]
     pub warning_duration: u64,
     pub mode: OperationMode,
+
+    pub locale: Option<String>, // overrides the system locale for notifications
 }
 
 impl Default for BreakConfig {
@@ -32,21 +261,72 @@ impl Default for BreakConfig {
             microbreak_interval: 180, // 3 min
             microbreak_duration: 30,
             microbreak_enabled: true,
+            microbreak_strictness: BreakStrictness::Skippable,
+            microbreak_escalation: EscalationConfig { overlay_after: 10, fullscreen_after: 60 },
 
             rest_interval: 2700, // 45 min
             rest_duration: 600,  // 10 min
             rest_enabled: true,
+            rest_strictness: BreakStrictness::Skippable,
+            rest_escalation: EscalationConfig { overlay_after: 30, fullscreen_after: 120 },
+            rest_partial_credit: 0,
+
+            coalesce_window: 300, // 5 min
+
+            postpone_duration: 120, // 2 min
+            max_postpones: 0,
+            break_input_policy: BreakInputPolicy::Pause,
+            reprompt_interval: 60,
+            overlay_focus: FocusPolicy::OnEscalation,
+            shortcuts: ShortcutConfig::default(),
+            api_socket: false,
+            lock_screen_idle: true,
+            defer: DeferConfig::default(),
+            input: InputConfig::default(),
+            calendar: None,
+            hooks: HookConfig::default(),
 
             daily_limit: 28800, // 8 hours
             daily_enabled: true,
 
             warning_duration: 30,
             mode: OperationMode::Normal,
+
+            locale: None,
         }
     }
 }
 
-#[derive(Debug, Serialize, Clone, Copy)]
+/// In-break countdown owned by the backend.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakCountdown {
+    pub break_type: BreakType,
+    pub duration: u64,
+    pub remaining: u64,
+    pub input_during_break: bool,
+    pub prompts: u32, // first prompt plus repeats
+    pub elapsed: u64, // seconds since the break started
+    pub ignored: u64, // seconds of input since the break started
+    pub escalation: EscalationLevel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimerEvent {
+    BreakStarted(BreakType),
+    BreakInterrupted(BreakType), // first input seen during the break
+    BreakReprompted { break_type: BreakType, repeat: u32 },
+    BreakEscalated { break_type: BreakType, level: EscalationLevel },
+    BreakCompleted(BreakType),
+    BreakEnded(BreakType), // ended without being taken: worked through, or superseded by a rest break
+    BreakWarning(BreakType), // warning_duration seconds of activity before it is due
+    BreakSkipped(BreakType),
+    DailyLimitReached,
+    NaturalBreak(BreakType),  // taken away from the computer without a prompt
+    BreakDeferred(BreakType), // held back while busy, reported once per break
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 #[serde(rename_all = "camelCase")]
 pub struct TimerStatus {
     pub daily_usage: u64,
@@ -62,7 +342,10 @@ pub struct TimerStatus {
 
     pub current_idle: u64,
 
+    pub active_break: Option<BreakCountdown>,
+
     pub mode: OperationMode,
+    pub suspend_remaining: Option<u64>,
 }
 
 pub struct TimerService {
@@ -73,16 +356,72 @@ pub struct TimerService {
     pub rest_active: u64,
 
     pub current_idle: u64,
+
+    pub active_break: Option<BreakCountdown>,
+    // Postpones used since each break was last taken
+    pub micro_postpones: u32,
+    pub rest_postpones: u32,
+    // Seconds of input since the running break was last prompted
+    ignored_seconds: u64,
+
+    // Fractional rest credit carried over between idle ticks
+    rest_credit_remainder: u64,
+
+    // Seconds left of a timed suspension, and the mode to resume afterwards
+    pub suspend_remaining: Option<u64>,
+    resume_mode: OperationMode,
+
+    // Events from actions taken between ticks, reported by the next tick
+    pending_events: Vec<TimerEvent>,
+
+    // Idle seconds since the session was locked or went to sleep
+    away_idle: Option<u64>,
+
+    // Whether the user is in a context breaks should not interrupt, and the
+    // seconds the due break has been held back for it
+    busy: bool,
+    deferred: u64,
+    // Whether a calendar meeting is on, which holds due breaks back until it ends
+    in_meeting: bool,
+    // A break asked for through a trigger is never held back
+    break_requested: bool,
 }
 
 impl TimerService {
     pub fn new(config: BreakConfig) -> Self {
-        Self { config, daily_usage: 0, micro_active: 0, rest_active: 0, current_idle: 0 }
+        Self {
+            config,
+            daily_usage: 0,
+            micro_active: 0,
+            rest_active: 0,
+            current_idle: 0,
+            active_break: None,
+            micro_postpones: 0,
+            rest_postpones: 0,
+            ignored_seconds: 0,
+            rest_credit_remainder: 0,
+            suspend_remaining: None,
+            resume_mode: OperationMode::Normal,
+            pending_events: Vec::new(),
+            away_idle: None,
+            busy: false,
+            deferred: 0,
+            in_meeting: false,
+            break_requested: false,
+        }
     }
 
-    pub fn tick(&mut self, is_idle: bool) {
+    pub fn tick(&mut self, is_idle: bool) -> Vec<TimerEvent> {
+        let mut events = std::mem::take(&mut self.pending_events);
+
         if self.config.mode == OperationMode::Suspended {
-            return;
+            if let Some(remaining) = self.suspend_remaining {
+                self.suspend_remaining = Some(remaining.saturating_sub(1));
+                if remaining <= 1 {
+                    self.resume();
+                }
+            }
+            return events;
         }
 
         if is_idle {
@@ -95,6 +434,7 @@ impl TimerService {
                 // If we were accumulating active time, reset it
                 if self.micro_active > 0 {
                     self.micro_active = 0;
+                    self.micro_postpones = 0;
                     // Note: In a real app, we might want to "cap" the idle at duration
                     // or let it grow. Green bar usually fills then stops?
                 }
@@ -106,6 +446,22 @@ impl TimerService {
                 && self.rest_active > 0
             {
                 self.rest_active = 0;
+                self.rest_credit_remainder = 0;
+                self.rest_postpones = 0;
+            } else if self.config.rest_enabled
+                && self.config.rest_partial_credit > 0
+                && self.current_idle >= self.config.microbreak_duration
+                && self.rest_active <= self.config.rest_interval
+            {
+                // An overdue rest break still needs the full rest duration.
+                // Idle long enough to count as a microbreak earns partial rest.
+                // The first qualifying tick credits the whole stretch so far.
+                let seconds = if self.current_idle == self.config.microbreak_duration {
+                    self.current_idle
+                } else {
+                    1
+                };
+                self.credit_rest(seconds);
             }
         } else {
             // User Active
@@ -118,7 +474,230 @@ impl TimerService {
             if self.config.rest_enabled {
                 self.rest_active = self.rest_active.saturating_add(1);
             }
+
+            let warning = self.config.warning_duration;
+            if warning > 0 {
+                if self.config.microbreak_enabled
+                    && self.micro_active + warning == self.config.microbreak_interval
+                {
+                    events.push(TimerEvent::BreakWarning(BreakType::Micro));
+                }
+                if self.config.rest_enabled
+                    && self.rest_active + warning == self.config.rest_interval
+                {
+                    events.push(TimerEvent::BreakWarning(BreakType::Rest));
+                }
+            }
+            if self.config.daily_enabled && self.daily_usage == self.config.daily_limit {
+                events.push(TimerEvent::DailyLimitReached);
+            }
+        }
+
+        self.advance_break(is_idle, &mut events);
+
+        if let Some(seconds) = &mut self.away_idle {
+            // A prompted break ending while away starts the count over, so it
+            // is not counted as a natural break as well
+            if events.iter().any(|event| {
+                matches!(event, TimerEvent::BreakCompleted(_) | TimerEvent::BreakEnded(_))
+            }) {
+                *seconds = 0;
+            } else if is_idle {
+                *seconds += 1;
+            }
+        }
+        events
+    }
+
+    /// Counts seconds the timer could not tick through, such as while the
+    /// computer slept, as idle time. Events are reported by the next tick.
+    pub fn add_idle_time(&mut self, seconds: u64) {
+        for _ in 0..seconds {
+            self.pending_events = self.tick(true);
+        }
+    }
+
+    /// Marks the session as locked or asleep, or back. A stretch away that was
+    /// long enough is reported as a natural break by the next tick.
+    pub fn set_away(&mut self, away: bool) {
+        match (self.away_idle, away) {
+            (None, true) => self.away_idle = Some(0),
+            (Some(seconds), false) => {
+                self.away_idle = None;
+                let break_type = if self.config.rest_enabled && seconds >= self.config.rest_duration
+                {
+                    Some(BreakType::Rest)
+                } else if self.config.microbreak_enabled
+                    && seconds >= self.config.microbreak_duration
+                {
+                    Some(BreakType::Micro)
+                } else {
+                    None
+                };
+                if let Some(break_type) = break_type {
+                    self.pending_events.push(TimerEvent::NaturalBreak(break_type));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Runs the in-break countdown: starts it when a break becomes due, counts it
+    /// down and ends the break once it reaches zero.
+    fn advance_break(&mut self, is_idle: bool, events: &mut Vec<TimerEvent>) {
+        if let Some(mut countdown) = self.active_break {
+            let break_type = countdown.break_type;
+
+            if !self.is_overdue(break_type) {
+                // Cleared by idle time on this tick
+                self.active_break = None;
+                events.push(if countdown.input_during_break {
+                    TimerEvent::BreakEnded(break_type)
+                } else {
+                    TimerEvent::BreakCompleted(break_type)
+                });
+            } else if break_type == BreakType::Micro && self.due_break() == Some(BreakType::Rest) {
+                // Superseded by the rest break, which starts below
+                self.active_break = None;
+                events.push(TimerEvent::BreakEnded(break_type));
+            } else {
+                let past_grace = countdown.elapsed >= BREAK_INPUT_GRACE_SECS;
+                if !is_idle && past_grace && !countdown.input_during_break {
+                    countdown.input_during_break = true;
+                    events.push(TimerEvent::BreakInterrupted(break_type));
+                }
+
+                countdown.remaining = match self.config.break_input_policy {
+                    BreakInputPolicy::Pause if !is_idle => countdown.remaining,
+                    BreakInputPolicy::Restart if !is_idle => countdown.duration,
+                    _ => countdown.remaining.saturating_sub(1),
+                };
+
+                // Prompt again while the user keeps working through the break
+                if !is_idle && self.config.reprompt_interval > 0 {
+                    self.ignored_seconds += 1;
+                    if self.ignored_seconds >= self.config.reprompt_interval {
+                        self.ignored_seconds = 0;
+                        events.push(TimerEvent::BreakReprompted {
+                            break_type,
+                            repeat: countdown.prompts,
+                        });
+                        countdown.prompts += 1;
+                    }
+                }
+
+                countdown.elapsed = countdown.elapsed.saturating_add(1);
+                // Only working through the break escalates it, not resting at it
+                if !is_idle {
+                    countdown.ignored = countdown.ignored.saturating_add(1);
+                }
+                let level = self.escalation(break_type).level_at(countdown.ignored);
+                if level > countdown.escalation {
+                    countdown.escalation = level;
+                    events.push(TimerEvent::BreakEscalated { break_type, level });
+                }
+
+                if countdown.remaining == 0 {
+                    self.clear_break(break_type);
+                    events.push(if countdown.input_during_break {
+                        TimerEvent::BreakEnded(break_type)
+                    } else {
+                        TimerEvent::BreakCompleted(break_type)
+                    });
+                } else {
+                    self.active_break = Some(countdown);
+                }
+            }
+        }
+
+        if self.active_break.is_none() {
+            match self.due_break() {
+                Some(break_type) if self.defers_break() => {
+                    if self.deferred == 0 {
+                        events.push(TimerEvent::BreakDeferred(break_type));
+                    }
+                    self.deferred += 1;
+                }
+                Some(break_type) => {
+                    self.start_break(break_type);
+                    events.push(TimerEvent::BreakStarted(break_type));
+                }
+                None => self.deferred = 0,
+            }
+        }
+    }
+
+    /// Whether a due break should wait: until the meeting ends, or for at most
+    /// `max_defer` seconds while busy.
+    fn defers_break(&self) -> bool {
+        !self.break_requested
+            && (self.in_meeting || self.busy && self.deferred < self.config.defer.max_defer)
+    }
+
+    /// Marks the user as presenting, watching or otherwise not to be
+    /// interrupted, or not. Due breaks wait while busy.
+    pub fn set_busy(&mut self, busy: bool) {
+        self.busy = busy;
+    }
+
+    /// Marks a calendar meeting as on, or over. When one ends, a rest break
+    /// that would come due within `coalesce_window` seconds is brought forward
+    /// to start right away.
+    pub fn set_in_meeting(&mut self, in_meeting: bool) {
+        if self.in_meeting && !in_meeting {
+            let rest_soon =
+                self.rest_active + self.config.coalesce_window >= self.config.rest_interval;
+            if self.config.rest_enabled && rest_soon && self.active_break.is_none() {
+                self.rest_active = self.rest_active.max(self.config.rest_interval + 1);
+            }
         }
+        self.in_meeting = in_meeting;
+    }
+
+    fn start_break(&mut self, break_type: BreakType) {
+        let duration = self.break_duration(break_type);
+        self.active_break = Some(BreakCountdown {
+            break_type,
+            duration,
+            remaining: duration,
+            input_during_break: false,
+            prompts: 1,
+            elapsed: 0,
+            ignored: 0,
+            escalation: self.escalation(break_type).level_at(0),
+        });
+        self.ignored_seconds = 0;
+        self.deferred = 0;
+        self.break_requested = false;
+    }
+
+    fn is_overdue(&self, break_type: BreakType) -> bool {
+        match break_type {
+            BreakType::Micro => self.micro_active > self.config.microbreak_interval,
+            BreakType::Rest => self.rest_active > self.config.rest_interval,
+        }
+    }
+
+    fn clear_break(&mut self, break_type: BreakType) {
+        match break_type {
+            BreakType::Micro => self.reset_microbreak(),
+            BreakType::Rest => self.reset_rest_break(),
+        }
+    }
+
+    /// Reduces `rest_active` in proportion to `idle_seconds / rest_duration`,
+    /// scaled by the configured partial credit percentage.
+    fn credit_rest(&mut self, idle_seconds: u64) {
+        let percent = self.config.rest_partial_credit.min(100);
+        let denominator = self.config.rest_duration.max(1).saturating_mul(100);
+
+        self.rest_credit_remainder = self.rest_credit_remainder.saturating_add(
+            self.config.rest_interval.saturating_mul(percent).saturating_mul(idle_seconds),
+        );
+        let credit = self.rest_credit_remainder / denominator;
+        self.rest_credit_remainder %= denominator;
+
+        self.rest_active = self.rest_active.saturating_sub(credit);
     }
 
     pub fn update_config(&mut self, new_config: BreakConfig) {
@@ -127,25 +706,233 @@ impl TimerService {
 
     pub fn reset_microbreak(&mut self) {
         self.micro_active = 0;
+        self.micro_postpones = 0;
+        if self.is_running(BreakType::Micro) {
+            self.active_break = None;
+        }
     }
 
     pub fn reset_rest_break(&mut self) {
         self.rest_active = 0;
+        self.rest_credit_remainder = 0;
+        self.rest_postpones = 0;
+        // A rest break always covers a pending microbreak
+        self.micro_active = 0;
+        self.micro_postpones = 0;
+        self.active_break = None;
+    }
+
+    /// Resets a break the user chose to skip, if its strictness allows it.
+    pub fn skip_break(&mut self, break_type: BreakType) -> Result<(), String> {
+        self.check_skip(break_type)?;
+        match break_type {
+            BreakType::Micro => self.reset_microbreak(),
+            BreakType::Rest => self.reset_rest_break(),
+        }
+        self.pending_events.push(TimerEvent::BreakSkipped(break_type));
+        Ok(())
+    }
+
+    /// Pushes a break back by `postpone_duration` seconds of activity.
+    pub fn postpone_break(&mut self, break_type: BreakType) -> Result<(), String> {
+        self.check_postpone(break_type)?;
+        if self.postpones_left(break_type) == Some(0) {
+            return Err("No postpones left".to_string());
+        }
+
+        let postpone = self.config.postpone_duration;
+        if self.is_running(break_type) {
+            self.active_break = None;
+        }
+        match break_type {
+            BreakType::Micro => {
+                self.micro_active = self.config.microbreak_interval.saturating_sub(postpone);
+                self.micro_postpones += 1;
+            }
+            BreakType::Rest => {
+                self.rest_active = self.config.rest_interval.saturating_sub(postpone);
+                self.rest_postpones += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Postpones remaining for `break_type`, or `None` when unlimited.
+    pub fn postpones_left(&self, break_type: BreakType) -> Option<u32> {
+        if self.config.max_postpones == 0 {
+            return None;
+        }
+        let used = match break_type {
+            BreakType::Micro => self.micro_postpones,
+            BreakType::Rest => self.rest_postpones,
+        };
+        Some(self.config.max_postpones.saturating_sub(used))
+    }
+
+    /// Seconds of activity until `break_type` comes due, or `None` if it is
+    /// disabled or already due.
+    pub fn seconds_until(&self, break_type: BreakType) -> Option<u64> {
+        let (enabled, active, interval) = match break_type {
+            BreakType::Micro => {
+                (self.config.microbreak_enabled, self.micro_active, self.config.microbreak_interval)
+            }
+            BreakType::Rest => {
+                (self.config.rest_enabled, self.rest_active, self.config.rest_interval)
+            }
+        };
+        (enabled && active <= interval).then(|| interval - active + 1)
+    }
+
+    pub fn strictness(&self, break_type: BreakType) -> BreakStrictness {
+        match break_type {
+            BreakType::Micro => self.config.microbreak_strictness,
+            BreakType::Rest => self.config.rest_strictness,
+        }
+    }
+
+    pub fn escalation(&self, break_type: BreakType) -> EscalationConfig {
+        match break_type {
+            BreakType::Micro => self.config.microbreak_escalation,
+            BreakType::Rest => self.config.rest_escalation,
+        }
+    }
+
+    pub fn break_duration(&self, break_type: BreakType) -> u64 {
+        match break_type {
+            BreakType::Micro => self.config.microbreak_duration,
+            BreakType::Rest => self.config.rest_duration,
+        }
+    }
+
+    /// Whether `break_type` has a countdown in progress.
+    fn is_running(&self, break_type: BreakType) -> bool {
+        self.active_break.is_some_and(|countdown| countdown.break_type == break_type)
+    }
+
+    /// Whether the running break is locked and must not be dismissed.
+    pub fn is_locked(&self) -> bool {
+        self.active_break.is_some_and(|countdown| {
+            self.strictness(countdown.break_type) == BreakStrictness::Locked
+        })
+    }
+
+    /// Rejects ending a running break early unless it is skippable.
+    pub fn check_skip(&self, break_type: BreakType) -> Result<(), String> {
+        match self.strictness(break_type) {
+            BreakStrictness::Skippable => Ok(()),
+            _ if !self.is_running(break_type) => Ok(()),
+            BreakStrictness::Postponable => Err("Break can only be postponed".to_string()),
+            BreakStrictness::Locked => Err("Break is locked".to_string()),
+        }
+    }
+
+    /// Actions the user may take on `break_type` given its strictness.
+    pub fn available_actions(&self, break_type: BreakType) -> Vec<BreakAction> {
+        match self.strictness(break_type) {
+            BreakStrictness::Skippable => {
+                vec![BreakAction::TakeNow, BreakAction::Postpone, BreakAction::Skip]
+            }
+            BreakStrictness::Postponable => vec![BreakAction::TakeNow, BreakAction::Postpone],
+            BreakStrictness::Locked => vec![BreakAction::TakeNow],
+        }
+        .into_iter()
+        .filter(|&action| {
+            action != BreakAction::Postpone || self.postpones_left(break_type) != Some(0)
+        })
+        .collect()
+    }
+
+    /// Rejects postponing a running break that is locked.
+    pub fn check_postpone(&self, break_type: BreakType) -> Result<(), String> {
+        if self.strictness(break_type) == BreakStrictness::Locked && self.is_running(break_type) {
+            return Err("Break is locked".to_string());
+        }
+        Ok(())
     }
 
     pub fn set_mode(&mut self, mode: OperationMode) {
         self.config.mode = mode;
+        self.suspend_remaining = None;
+    }
+
+    /// Switches Normal -> Quiet -> Suspended -> Normal and returns the new mode.
+    pub fn cycle_mode(&mut self) -> OperationMode {
+        let next = match self.config.mode {
+            OperationMode::Normal => OperationMode::Quiet,
+            OperationMode::Quiet => OperationMode::Suspended,
+            OperationMode::Suspended => OperationMode::Normal,
+        };
+        self.set_mode(next);
+        next
+    }
+
+    /// Suspends the timer for `seconds`, then returns to the current mode.
+    pub fn suspend_for(&mut self, seconds: u64) {
+        if self.config.mode != OperationMode::Suspended {
+            self.resume_mode = self.config.mode;
+        }
+        self.set_mode(OperationMode::Suspended);
+        self.suspend_remaining = Some(seconds);
+    }
+
+    /// Ends a timed suspension early.
+    pub fn resume(&mut self) {
+        self.set_mode(self.resume_mode);
     }
 
     pub fn trigger_rest_break(&mut self) {
         // Set active time just above the interval to trigger 'overdue' logic
         // The actual overlay logic depends on the frontend seeing 'rest_is_overdue'
         self.rest_active = self.config.rest_interval + 1;
+        self.break_requested = true;
     }
 
     pub fn trigger_microbreak(&mut self) {
         // Set active time just above the interval to trigger 'overdue' logic
         self.micro_active = self.config.microbreak_interval + 1;
+        self.break_requested = true;
+    }
+
+    /// Starts a break right away, skipping the notification stage.
+    pub fn take_break_now(&mut self, break_type: BreakType) {
+        match break_type {
+            BreakType::Micro => self.trigger_microbreak(),
+            BreakType::Rest => self.trigger_rest_break(),
+        }
+        if !self.is_running(break_type) {
+            self.start_break(break_type);
+        }
+        if let Some(countdown) = self.active_break.as_mut() {
+            countdown.escalation = countdown.escalation.max(EscalationLevel::Overlay);
+        }
+    }
+
+    /// Whether a due microbreak should be folded into the upcoming rest break.
+    ///
+    /// The rest break always wins: if it is overdue, or will be within
+    /// `coalesce_window` seconds, the microbreak is not reported as overdue and
+    /// is cleared together with the rest break.
+    pub fn micro_coalesced(&self) -> bool {
+        self.config.rest_enabled
+            && self.rest_active.saturating_add(self.config.coalesce_window)
+                >= self.config.rest_interval
+    }
+
+    /// The break that should be prompted now, if any. Rest wins over micro.
+    pub fn due_break(&self) -> Option<BreakType> {
+        let status = self.get_status();
+        if status.rest_is_overdue {
+            Some(BreakType::Rest)
+        } else if status.micro_is_overdue {
+            Some(BreakType::Micro)
+        } else {
+            None
+        }
+    }
+
+    /// The running break, or else the break that is due.
+    pub fn current_break(&self) -> Option<BreakType> {
+        self.active_break.map(|countdown| countdown.break_type).or_else(|| self.due_break())
     }
 
     pub fn get_status(&self) -> TimerStatus {
@@ -155,14 +942,17 @@ impl TimerService {
 
             micro_active: self.micro_active,
             micro_target: self.config.microbreak_interval,
-            micro_is_overdue: self.micro_active > self.config.microbreak_interval,
+            micro_is_overdue: self.micro_active > self.config.microbreak_interval
+                && !self.micro_coalesced(),
 
             rest_active: self.rest_active,
             rest_target: self.config.rest_interval,
             rest_is_overdue: self.rest_active > self.config.rest_interval,
 
             current_idle: self.current_idle,
+            active_break: self.active_break,
             mode: self.config.mode,
+            suspend_remaining: self.suspend_remaining,
         }
     }
 }
@@ -204,6 +994,444 @@ mod tests {
         assert_eq!(service.micro_active, 1);
     }
 
+    #[test]
+    fn test_partial_rest_credit() {
+        let mut config = BreakConfig::default();
+        config.microbreak_duration = 2;
+        config.rest_interval = 100;
+        config.rest_duration = 10;
+        config.rest_partial_credit = 100;
+        let mut service = TimerService::new(config);
+
+        for _ in 0..50 {
+            service.tick(false);
+        }
+        assert_eq!(service.rest_active, 50);
+
+        // Shorter than a microbreak: no credit
+        service.tick(true);
+        assert_eq!(service.rest_active, 50);
+
+        // Reaching the microbreak duration credits the whole stretch (2/10 of 100)
+        service.tick(true);
+        assert_eq!(service.rest_active, 30);
+
+        // Each further idle second is worth 1/10 of the interval
+        service.tick(true);
+        assert_eq!(service.rest_active, 20);
+
+        // Several short pauses add up
+        service.tick(false);
+        service.tick(true);
+        service.tick(true);
+        assert_eq!(service.rest_active, 1);
+    }
+
+    #[test]
+    fn test_partial_rest_credit_scaled_and_disabled() {
+        let mut config = BreakConfig::default();
+        config.microbreak_duration = 1;
+        config.rest_interval = 100;
+        config.rest_duration = 10;
+        config.rest_partial_credit = 50;
+        let mut service = TimerService::new(config.clone());
+
+        for _ in 0..40 {
+            service.tick(false);
+        }
+        for _ in 0..4 {
+            service.tick(true);
+        }
+        assert_eq!(service.rest_active, 20);
+
+        config.rest_partial_credit = 0;
+        let mut service = TimerService::new(config);
+        for _ in 0..40 {
+            service.tick(false);
+        }
+        for _ in 0..4 {
+            service.tick(true);
+        }
+        assert_eq!(service.rest_active, 40);
+    }
+
+    #[test]
+    fn test_overdue_rest_not_cleared_by_microbreak_idle() {
+        let mut config = BreakConfig::default();
+        config.rest_partial_credit = 100;
+        let mut service = TimerService::new(config);
+        service.rest_active = service.config.rest_interval + 1;
+
+        // A microbreak's worth of idle earns no credit once rest is overdue
+        for _ in 0..service.config.microbreak_duration + 5 {
+            service.tick(true);
+        }
+        assert_eq!(service.rest_active, service.config.rest_interval + 1);
+        assert!(service.get_status().rest_is_overdue);
+    }
+
+    #[test]
+    fn test_microbreak_coalesced_into_upcoming_rest() {
+        let mut config = BreakConfig::default();
+        config.microbreak_interval = 10;
+        config.rest_interval = 20;
+        config.coalesce_window = 5;
+        let mut service = TimerService::new(config);
+
+        for _ in 0..11 {
+            service.tick(false);
+        }
+        // Rest is 9s away, outside the window: microbreak shows
+        assert!(service.get_status().micro_is_overdue);
+
+        for _ in 0..4 {
+            service.tick(false);
+        }
+        // Rest is 5s away: microbreak is suppressed
+        let status = service.get_status();
+        assert!(!status.micro_is_overdue);
+        assert!(!status.rest_is_overdue);
+
+        // Taking the rest break clears the microbreak too
+        service.reset_rest_break();
+        assert_eq!(service.micro_active, 0);
+    }
+
+    #[test]
+    fn test_rest_wins_when_both_overdue() {
+        let mut config = BreakConfig::default();
+        config.coalesce_window = 0;
+        let mut service = TimerService::new(config);
+
+        service.trigger_microbreak();
+        service.trigger_rest_break();
+
+        let status = service.get_status();
+        assert!(status.rest_is_overdue);
+        assert!(!status.micro_is_overdue);
+    }
+
+    #[test]
+    fn test_locked_break_rejects_skip_and_postpone() {
+        let mut config = BreakConfig::default();
+        config.rest_strictness = BreakStrictness::Locked;
+        config.rest_duration = 3;
+        let mut service = TimerService::new(config);
+
+        service.trigger_rest_break();
+        service.tick(false);
+        assert_eq!(service.active_break.map(|b| b.break_type), Some(BreakType::Rest));
+        assert!(service.is_locked());
+        assert!(service.check_skip(BreakType::Rest).is_err());
+        assert!(service.postpone_break(BreakType::Rest).is_err());
+
+        for _ in 0..3 {
+            service.tick(true);
+        }
+        assert!(!service.is_locked());
+        assert!(service.check_skip(BreakType::Rest).is_ok());
+    }
+
+    #[test]
+    fn test_postponable_break() {
+        let mut config = BreakConfig::default();
+        config.microbreak_strictness = BreakStrictness::Postponable;
+        config.microbreak_interval = 100;
+        config.postpone_duration = 30;
+        let mut service = TimerService::new(config);
+
+        service.trigger_microbreak();
+        service.tick(false);
+        assert!(service.check_skip(BreakType::Micro).is_err());
+        assert!(!service.is_locked());
+
+        service.postpone_break(BreakType::Micro).unwrap();
+        assert_eq!(service.micro_active, 70);
+        assert!(!service.get_status().micro_is_overdue);
+    }
+
+    #[test]
+    fn test_postpone_limit() {
+        let mut config = BreakConfig::default();
+        config.max_postpones = 2;
+        let mut service = TimerService::new(config);
+
+        assert_eq!(service.postpones_left(BreakType::Rest), Some(2));
+        service.postpone_break(BreakType::Rest).unwrap();
+        service.postpone_break(BreakType::Rest).unwrap();
+        assert_eq!(service.postpones_left(BreakType::Rest), Some(0));
+        assert!(service.postpone_break(BreakType::Rest).is_err());
+        assert!(!service.available_actions(BreakType::Rest).contains(&BreakAction::Postpone));
+
+        // Taking the break restores the allowance
+        service.reset_rest_break();
+        assert_eq!(service.postpones_left(BreakType::Rest), Some(2));
+    }
+
+    #[test]
+    fn test_break_countdown() {
+        let mut config = BreakConfig::default();
+        config.microbreak_interval = 5;
+        config.microbreak_duration = 3;
+        let mut service = TimerService::new(config);
+
+        for _ in 0..5 {
+            assert!(service.tick(false).is_empty());
+        }
+        assert_eq!(service.tick(false), vec![TimerEvent::BreakStarted(BreakType::Micro)]);
+
+        let countdown = service.get_status().active_break.unwrap();
+        assert_eq!(countdown.remaining, 3);
+        assert!(!countdown.input_during_break);
+
+        service.tick(true);
+        assert_eq!(service.get_status().active_break.unwrap().remaining, 2);
+        service.tick(true);
+        assert_eq!(service.get_status().active_break.unwrap().remaining, 1);
+
+        assert_eq!(service.tick(true), vec![TimerEvent::BreakCompleted(BreakType::Micro)]);
+        assert!(service.active_break.is_none());
+        assert_eq!(service.micro_active, 0);
+    }
+
+    #[test]
+    fn test_input_during_break_policies() {
+        let mut config = BreakConfig::default();
+        config.microbreak_interval = 0;
+        config.microbreak_duration = 6;
+
+        let remaining_after = |policy: BreakInputPolicy| {
+            let mut config = config.clone();
+            config.break_input_policy = policy;
+            let mut service = TimerService::new(config);
+
+            service.tick(false); // break starts
+
+            // Idle through the grace period
+            for _ in 0..BREAK_INPUT_GRACE_SECS {
+                service.tick(true);
+            }
+            assert_eq!(service.tick(false), vec![TimerEvent::BreakInterrupted(BreakType::Micro)]);
+            // Further input is not reported again
+            assert!(service.tick(false).is_empty());
+
+            let countdown = service.active_break.unwrap();
+            assert!(countdown.input_during_break);
+            countdown.remaining
+        };
+
+        assert_eq!(remaining_after(BreakInputPolicy::Ignore), 1);
+        assert_eq!(remaining_after(BreakInputPolicy::Pause), 3);
+        assert_eq!(remaining_after(BreakInputPolicy::Restart), 6);
+    }
+
+    #[test]
+    fn test_interrupted_break_is_not_completed() {
+        let mut config = BreakConfig::default();
+        config.microbreak_interval = 0;
+        config.microbreak_duration = 5;
+        config.break_input_policy = BreakInputPolicy::Ignore;
+        let mut service = TimerService::new(config);
+
+        service.tick(false); // break starts
+        let events: Vec<_> = (0..5).flat_map(|_| service.tick(false)).collect();
+        assert_eq!(
+            events,
+            [
+                TimerEvent::BreakInterrupted(BreakType::Micro),
+                TimerEvent::BreakEnded(BreakType::Micro)
+            ]
+        );
+        assert!(service.active_break.is_none());
+    }
+
+    #[test]
+    fn test_interrupted_break_cleared_by_idle_is_not_completed() {
+        let mut config = BreakConfig::default();
+        config.microbreak_interval = 0;
+        config.microbreak_duration = 5;
+        config.break_input_policy = BreakInputPolicy::Pause;
+        let mut service = TimerService::new(config);
+
+        service.tick(false); // break starts
+        let events: Vec<_> = (0..4).flat_map(|_| service.tick(false)).collect();
+        assert_eq!(events, [TimerEvent::BreakInterrupted(BreakType::Micro)]);
+        assert_eq!(service.active_break.unwrap().remaining, 5);
+
+        // Going idle for the whole duration clears the break before the countdown ends
+        for _ in 0..4 {
+            assert!(service.tick(true).is_empty());
+        }
+        assert_eq!(service.tick(true), [TimerEvent::BreakEnded(BreakType::Micro)]);
+        assert!(service.active_break.is_none());
+    }
+
+    #[test]
+    fn test_running_microbreak_superseded_by_rest() {
+        let mut config = BreakConfig::default();
+        config.microbreak_interval = 0;
+        config.rest_interval = 6;
+        config.coalesce_window = 3;
+        let mut service = TimerService::new(config);
+
+        assert_eq!(service.tick(false), [TimerEvent::BreakStarted(BreakType::Micro)]);
+
+        // Coming within the coalesce window does not drop the running microbreak
+        for _ in 0..4 {
+            service.tick(false);
+        }
+        assert!(service.micro_coalesced());
+        assert_eq!(service.active_break.unwrap().break_type, BreakType::Micro);
+
+        // Once the rest break is due it replaces the microbreak right away
+        service.tick(false);
+        assert_eq!(
+            service.tick(false),
+            [TimerEvent::BreakEnded(BreakType::Micro), TimerEvent::BreakStarted(BreakType::Rest)]
+        );
+        assert_eq!(service.active_break.unwrap().break_type, BreakType::Rest);
+    }
+
+    #[test]
+    fn test_input_right_after_break_starts_is_forgiven() {
+        let mut config = BreakConfig::default();
+        config.microbreak_interval = 0;
+        config.microbreak_duration = 5;
+        let mut service = TimerService::new(config);
+
+        service.tick(false); // break starts
+
+        // Finishing a keystroke as the break pops up
+        service.tick(false);
+        service.tick(false);
+        assert!(!service.active_break.unwrap().input_during_break);
+
+        let events: Vec<_> = (0..5).flat_map(|_| service.tick(true)).collect();
+        assert_eq!(events, [TimerEvent::BreakCompleted(BreakType::Micro)]);
+    }
+
+    #[test]
+    fn test_reprompt_while_break_ignored() {
+        let mut config = BreakConfig::default();
+        config.reprompt_interval = 2;
+        let mut service = TimerService::new(config);
+
+        service.trigger_rest_break();
+        service.tick(false);
+
+        let mut repeats = Vec::new();
+        for _ in 0..5 {
+            for event in service.tick(false) {
+                if let TimerEvent::BreakReprompted { repeat, .. } = event {
+                    repeats.push(repeat);
+                }
+            }
+        }
+        assert_eq!(repeats, vec![1, 2]);
+        assert_eq!(service.active_break.unwrap().prompts, 3);
+
+        // Idle time during the break does not count as ignoring it
+        for _ in 0..3 {
+            assert!(service.tick(true).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_escalation_ladder() {
+        let mut config = BreakConfig::default();
+        config.rest_escalation = EscalationConfig { overlay_after: 2, fullscreen_after: 4 };
+        config.reprompt_interval = 0;
+        let mut service = TimerService::new(config);
+
+        service.trigger_rest_break();
+        service.tick(false);
+        assert_eq!(
+            service.get_status().active_break.unwrap().escalation,
+            EscalationLevel::Notification
+        );
+
+        let mut levels = Vec::new();
+        for _ in 0..5 {
+            for event in service.tick(false) {
+                if let TimerEvent::BreakEscalated { level, .. } = event {
+                    levels.push(level);
+                }
+            }
+        }
+        assert_eq!(levels, vec![EscalationLevel::Overlay, EscalationLevel::Fullscreen]);
+        assert_eq!(
+            service.get_status().active_break.unwrap().escalation,
+            EscalationLevel::Fullscreen
+        );
+    }
+
+    #[test]
+    fn test_idle_user_is_not_escalated() {
+        let mut config = BreakConfig::default();
+        config.rest_escalation = EscalationConfig { overlay_after: 2, fullscreen_after: 4 };
+        let mut service = TimerService::new(config);
+
+        service.trigger_rest_break();
+        service.tick(false);
+        for _ in 0..10 {
+            assert!(service.tick(true).is_empty());
+        }
+        let countdown = service.active_break.unwrap();
+        assert_eq!(countdown.escalation, EscalationLevel::Notification);
+        assert_eq!(countdown.elapsed, 10);
+
+        // Working through it again escalates from where the input left off
+        service.tick(false);
+        assert_eq!(
+            service.tick(false),
+            [TimerEvent::BreakEscalated {
+                break_type: BreakType::Rest,
+                level: EscalationLevel::Overlay
+            }]
+        );
+    }
+
+    #[test]
+    fn test_status_serializes_camel_case() {
+        let mut service = TimerService::new(BreakConfig::default());
+        service.trigger_microbreak();
+        service.tick(true);
+
+        let json = serde_json::to_value(service.get_status()).unwrap();
+        assert_eq!(json["microIsOverdue"], true);
+        assert_eq!(json["mode"], "normal");
+        let countdown = &json["activeBreak"];
+        assert_eq!(countdown["breakType"], "micro");
+        assert_eq!(countdown["remaining"], 30);
+        assert_eq!(countdown["inputDuringBreak"], false);
+    }
+
+    #[test]
+    fn test_take_break_now() {
+        let mut service = TimerService::new(BreakConfig::default());
+
+        service.take_break_now(BreakType::Rest);
+        let countdown = service.active_break.unwrap();
+        assert_eq!(countdown.break_type, BreakType::Rest);
+        assert_eq!(countdown.escalation, EscalationLevel::Overlay);
+
+        // The started break is not prompted again on the next tick
+        assert!(service.tick(true).is_empty());
+    }
+
+    #[test]
+    fn test_countdown_cancelled_by_reset() {
+        let mut service = TimerService::new(BreakConfig::default());
+
+        service.trigger_microbreak();
+        service.tick(false);
+        assert!(service.active_break.is_some());
+
+        service.reset_microbreak();
+        assert!(service.active_break.is_none());
+        assert!(service.tick(false).is_empty());
+    }
+
     #[test]
     fn test_suspended_mode() {
         let mut config = BreakConfig::default();
@@ -214,6 +1442,38 @@ mod tests {
         assert_eq!(service.daily_usage, 0); // Should not increase
     }
 
+    #[test]
+    fn test_cycle_mode() {
+        let mut service = TimerService::new(BreakConfig::default());
+        assert_eq!(service.cycle_mode(), OperationMode::Quiet);
+        assert_eq!(service.cycle_mode(), OperationMode::Suspended);
+        assert_eq!(service.cycle_mode(), OperationMode::Normal);
+        assert_eq!(service.config.mode, OperationMode::Normal);
+    }
+
+    #[test]
+    fn test_suspend_for_resumes_previous_mode() {
+        let mut service = TimerService::new(BreakConfig::default());
+        service.set_mode(OperationMode::Quiet);
+
+        service.suspend_for(3);
+        for _ in 0..2 {
+            service.tick(false);
+        }
+        assert_eq!(service.config.mode, OperationMode::Suspended);
+        assert_eq!(service.get_status().suspend_remaining, Some(1));
+        assert_eq!(service.daily_usage, 0);
+
+        service.tick(false);
+        assert_eq!(service.config.mode, OperationMode::Quiet);
+        assert_eq!(service.suspend_remaining, None);
+
+        // Choosing a mode ends a timed suspension
+        service.suspend_for(60);
+        service.set_mode(OperationMode::Normal);
+        assert_eq!(service.suspend_remaining, None);
+    }
+
     #[test]
     fn test_reset_microbreak() {
         let mut config = BreakConfig::default();
@@ -291,4 +1551,130 @@ mod tests {
         service.set_mode(OperationMode::Normal);
         assert_eq!(service.config.mode, OperationMode::Normal);
     }
+
+    #[test]
+    fn test_warning_and_daily_limit_events() {
+        let mut config = BreakConfig::default();
+        config.microbreak_interval = 40;
+        config.warning_duration = 10;
+        config.daily_limit = 31;
+        let mut service = TimerService::new(config);
+
+        let events: Vec<_> = (0..31).flat_map(|_| service.tick(false)).collect();
+        assert_eq!(
+            events,
+            [TimerEvent::BreakWarning(BreakType::Micro), TimerEvent::DailyLimitReached]
+        );
+    }
+
+    #[test]
+    fn test_skip_reported_by_next_tick() {
+        let mut service = TimerService::new(BreakConfig::default());
+        service.trigger_microbreak();
+        service.tick(false);
+
+        service.skip_break(BreakType::Micro).unwrap();
+        assert!(service.active_break.is_none());
+        assert_eq!(service.tick(true), [TimerEvent::BreakSkipped(BreakType::Micro)]);
+        assert!(service.tick(true).is_empty());
+    }
+
+    #[test]
+    fn test_away_stretch_counts_as_natural_break() {
+        let mut service = TimerService::new(BreakConfig::default());
+        service.micro_active = 100;
+        service.rest_active = 1000;
+
+        // Locked, then asleep for the rest of the rest duration
+        service.set_away(true);
+        for _ in 0..60 {
+            service.tick(true);
+        }
+        service.add_idle_time(540);
+        assert_eq!(service.rest_active, 0);
+        service.set_away(false);
+        assert_eq!(service.tick(false), [TimerEvent::NaturalBreak(BreakType::Rest)]);
+
+        service.set_away(true);
+        (0..30).for_each(|_| assert!(service.tick(true).is_empty()));
+        service.set_away(false);
+        assert_eq!(service.tick(false), [TimerEvent::NaturalBreak(BreakType::Micro)]);
+
+        service.set_away(true);
+        service.tick(true);
+        service.set_away(false);
+        assert!(service.tick(false).is_empty());
+    }
+
+    #[test]
+    fn test_prompted_break_while_away_is_not_natural() {
+        let mut service = TimerService::new(BreakConfig::default());
+        service.trigger_microbreak();
+        service.tick(false);
+
+        service.set_away(true);
+        let events: Vec<_> = (0..40).flat_map(|_| service.tick(true)).collect();
+        assert!(events.contains(&TimerEvent::BreakCompleted(BreakType::Micro)));
+        service.set_away(false);
+        assert!(service.tick(false).is_empty());
+    }
+
+    #[test]
+    fn test_busy_defers_break_up_to_max() {
+        let mut config = BreakConfig::default();
+        config.defer.max_defer = 3;
+        let mut service = TimerService::new(config);
+        service.micro_active = service.config.microbreak_interval;
+        service.set_busy(true);
+
+        assert_eq!(service.tick(false), [TimerEvent::BreakDeferred(BreakType::Micro)]);
+        assert!(service.tick(false).is_empty());
+        assert!(service.tick(false).is_empty());
+        assert!(service.active_break.is_none());
+        assert_eq!(service.tick(false), [TimerEvent::BreakStarted(BreakType::Micro)]);
+
+        // Deferred again once the next break is due, and no longer once free
+        service.reset_microbreak();
+        service.micro_active = service.config.microbreak_interval;
+        assert_eq!(service.tick(false), [TimerEvent::BreakDeferred(BreakType::Micro)]);
+        service.set_busy(false);
+        assert_eq!(service.tick(false), [TimerEvent::BreakStarted(BreakType::Micro)]);
+    }
+
+    #[test]
+    fn test_meeting_holds_breaks_until_it_ends() {
+        let mut service = TimerService::new(BreakConfig::default());
+        service.set_in_meeting(true);
+        service.micro_active = service.config.microbreak_interval;
+        service.rest_active = service.config.rest_interval - 600;
+
+        assert_eq!(service.tick(false), [TimerEvent::BreakDeferred(BreakType::Micro)]);
+        for _ in 0..service.config.defer.max_defer + 60 {
+            service.tick(false);
+        }
+        assert!(service.active_break.is_none());
+
+        // The rest break, due within the coalesce window, is brought forward
+        service.set_in_meeting(false);
+        assert_eq!(service.tick(false), [TimerEvent::BreakStarted(BreakType::Rest)]);
+    }
+
+    #[test]
+    fn test_meeting_end_coalesces_like_microbreaks() {
+        let mut service = TimerService::new(BreakConfig::default());
+        service.set_in_meeting(true);
+        service.rest_active = service.config.rest_interval - service.config.coalesce_window;
+        assert!(service.micro_coalesced());
+
+        service.set_in_meeting(false);
+        assert_eq!(service.tick(false), [TimerEvent::BreakStarted(BreakType::Rest)]);
+    }
+
+    #[test]
+    fn test_triggered_break_is_not_deferred() {
+        let mut service = TimerService::new(BreakConfig::default());
+        service.set_busy(true);
+        service.trigger_rest_break();
+        assert_eq!(service.tick(false), [TimerEvent::BreakStarted(BreakType::Rest)]);
+    }
 }
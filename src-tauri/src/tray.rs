@@ -0,0 +1,399 @@
+use crate::timer::{BreakAction, BreakType, OperationMode, TimerService, TimerStatus};
+use std::f64::consts::PI;
+
+/// Width and height of the rendered tray icon in pixels.
+pub const ICON_SIZE: u32 = 32;
+
+type Rgba = [u8; 4];
+
+const MICRO_COLOR: Rgba = [66, 153, 225, 255];
+const REST_COLOR: Rgba = [72, 187, 120, 255];
+const OVERDUE_COLOR: Rgba = [229, 62, 62, 255];
+const TRACK_COLOR: Rgba = [160, 160, 160, 90];
+const GLYPH_COLOR: Rgba = [160, 160, 160, 255];
+
+// Ring radii in pixels: rest break outside, microbreak inside
+const REST_RING: (f64, f64) = (12.0, 15.5);
+const MICRO_RING: (f64, f64) = (7.0, 10.5);
+
+/// Renders the tray icon as RGBA: the outer ring fills up towards the rest
+/// break and the inner ring towards the microbreak. A ring turns red once its
+/// break is due, and a pause glyph replaces both while suspended.
+pub fn render_icon(status: &TimerStatus) -> Vec<u8> {
+    let suspended = status.mode == OperationMode::Suspended;
+    let micro =
+        ring(status.micro_active, status.micro_target, due(status, BreakType::Micro), MICRO_COLOR);
+    let rest =
+        ring(status.rest_active, status.rest_target, due(status, BreakType::Rest), REST_COLOR);
+
+    let center = ICON_SIZE as f64 / 2.0;
+    let mut pixels = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+
+    for y in 0..ICON_SIZE {
+        for x in 0..ICON_SIZE {
+            let dx = x as f64 + 0.5 - center;
+            let dy = y as f64 + 0.5 - center;
+            let distance = dx.hypot(dy);
+            // Fraction of a turn, clockwise from 12 o'clock
+            let turn = (dx.atan2(-dy) / (2.0 * PI)).rem_euclid(1.0);
+
+            let pixel = if suspended && is_pause_glyph(x, y) {
+                GLYPH_COLOR
+            } else if let Some((fill, color)) = [(REST_RING, rest), (MICRO_RING, micro)]
+                .into_iter()
+                .find(|((inner, outer), _)| (*inner..*outer).contains(&distance))
+                .map(|(_, ring)| ring)
+            {
+                if !suspended && turn < fill {
+                    color
+                } else {
+                    TRACK_COLOR
+                }
+            } else {
+                [0, 0, 0, 0]
+            };
+            pixels.extend_from_slice(&pixel);
+        }
+    }
+    pixels
+}
+
+/// Tooltip text, e.g. "Microbreak in 1:42, rest in 23:10".
+pub fn tooltip(status: &TimerStatus) -> String {
+    if status.mode == OperationMode::Suspended {
+        return match status.suspend_remaining {
+            Some(remaining) => {
+                format!("RSI Assistant (suspended, {} left)", format_clock(remaining))
+            }
+            None => "RSI Assistant (suspended)".to_string(),
+        };
+    }
+    if let Some(countdown) = status.active_break {
+        let name = match countdown.break_type {
+            BreakType::Micro => "Microbreak",
+            BreakType::Rest => "Rest break",
+        };
+        return format!("{}: {} left", name, format_clock(countdown.remaining));
+    }
+
+    let micro = if due(status, BreakType::Micro) {
+        "Microbreak due".to_string()
+    } else {
+        format!("Microbreak in {}", format_clock(status.micro_target - status.micro_active))
+    };
+    let rest = if due(status, BreakType::Rest) {
+        "rest due".to_string()
+    } else {
+        format!("rest in {}", format_clock(status.rest_target - status.rest_active))
+    };
+    format!("{}, {}", micro, rest)
+}
+
+/// Remembers the last icon and tooltip handed to the tray so that it is only
+/// updated when they change.
+#[derive(Debug, Default)]
+pub struct TrayState {
+    icon: Vec<u8>,
+    tooltip: String,
+}
+
+impl TrayState {
+    /// Returns the icon and tooltip for `status`, each `None` when unchanged.
+    pub fn update(&mut self, status: &TimerStatus) -> (Option<Vec<u8>>, Option<String>) {
+        let icon = render_icon(status);
+        let icon = (icon != self.icon).then(|| {
+            self.icon = icon.clone();
+            icon
+        });
+
+        let tooltip = tooltip(status);
+        let tooltip = (tooltip != self.tooltip).then(|| {
+            self.tooltip = tooltip.clone();
+            tooltip
+        });
+
+        (icon, tooltip)
+    }
+}
+
+// Durations offered in the "Suspend for…" submenu, in minutes
+pub const SUSPEND_MINUTES: [u64; 4] = [15, 30, 60, 120];
+
+/// A clickable tray menu entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuEntry {
+    pub id: String,
+    pub label: String,
+}
+
+/// What a tray menu entry built by [`TrayMenu`] does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuAction {
+    Break(BreakType, BreakAction),
+    SuspendFor(u64), // seconds
+    Resume,
+}
+
+/// State-dependent part of the tray menu. Labels only carry whole minutes so
+/// that the menu is rebuilt at most once a minute while the timer runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrayMenu {
+    pub breaks: Vec<MenuEntry>,
+    pub suspend: Vec<MenuEntry>,
+    pub mode: OperationMode,
+}
+
+impl TrayMenu {
+    pub fn from_service(service: &TimerService) -> Self {
+        let mut breaks = Vec::new();
+        for break_type in [BreakType::Micro, BreakType::Rest] {
+            let (enabled, name) = match break_type {
+                BreakType::Micro => (service.config.microbreak_enabled, "microbreak"),
+                BreakType::Rest => (service.config.rest_enabled, "rest break"),
+            };
+            if !enabled {
+                continue;
+            }
+
+            let due_in = service.seconds_until(break_type);
+            let when = match due_in {
+                Some(seconds) => format!("in {} min", seconds.div_ceil(60)),
+                None => "due".to_string(),
+            };
+            let actions = service.available_actions(break_type);
+
+            breaks.push(MenuEntry {
+                id: menu_id(break_type, BreakAction::TakeNow),
+                label: format!("Take {} now ({})", name, when),
+            });
+            // Postponing only makes sense once the break is due
+            if due_in.is_none() && actions.contains(&BreakAction::Postpone) {
+                let label = match service.postpones_left(break_type) {
+                    Some(left) => format!("Postpone {} ({} left)", name, left),
+                    None => format!("Postpone {}", name),
+                };
+                breaks.push(MenuEntry { id: menu_id(break_type, BreakAction::Postpone), label });
+            }
+            if actions.contains(&BreakAction::Skip) {
+                breaks.push(MenuEntry {
+                    id: menu_id(break_type, BreakAction::Skip),
+                    label: format!("Skip {}", name),
+                });
+            }
+        }
+
+        let mut suspend = Vec::new();
+        if let Some(remaining) = service.suspend_remaining {
+            suspend.push(MenuEntry {
+                id: "resume".to_string(),
+                label: format!("Resume now ({} min left)", remaining.div_ceil(60)),
+            });
+        }
+        for minutes in SUSPEND_MINUTES {
+            let label = match minutes {
+                60 => "1 hour".to_string(),
+                m if m % 60 == 0 => format!("{} hours", m / 60),
+                m => format!("{} minutes", m),
+            };
+            suspend.push(MenuEntry { id: format!("suspend_{}", minutes), label });
+        }
+
+        Self { breaks, suspend, mode: service.config.mode }
+    }
+}
+
+fn menu_id(break_type: BreakType, action: BreakAction) -> String {
+    let action = match action {
+        BreakAction::TakeNow => "take",
+        BreakAction::Postpone => "postpone",
+        BreakAction::Skip => "skip",
+    };
+    let break_type = match break_type {
+        BreakType::Micro => "micro",
+        BreakType::Rest => "rest",
+    };
+    format!("{}_{}", action, break_type)
+}
+
+/// Action for a menu entry id built by [`TrayMenu`], `None` for any other entry.
+pub fn parse_menu_id(id: &str) -> Option<MenuAction> {
+    if id == "resume" {
+        return Some(MenuAction::Resume);
+    }
+    if let Some(minutes) = id.strip_prefix("suspend_") {
+        return minutes.parse::<u64>().ok().map(|minutes| MenuAction::SuspendFor(minutes * 60));
+    }
+
+    let (action, break_type) = id.split_once('_')?;
+    let action = match action {
+        "take" => BreakAction::TakeNow,
+        "postpone" => BreakAction::Postpone,
+        "skip" => BreakAction::Skip,
+        _ => return None,
+    };
+    Some(MenuAction::Break(break_type.parse().ok()?, action))
+}
+
+pub(crate) fn due(status: &TimerStatus, break_type: BreakType) -> bool {
+    let running = status.active_break.is_some_and(|countdown| countdown.break_type == break_type);
+    running
+        || match break_type {
+            BreakType::Micro => {
+                status.micro_is_overdue || status.micro_active >= status.micro_target
+            }
+            BreakType::Rest => status.rest_is_overdue || status.rest_active >= status.rest_target,
+        }
+}
+
+/// Fill fraction and colour of one ring.
+fn ring(active: u64, target: u64, due: bool, color: Rgba) -> (f64, Rgba) {
+    if due {
+        (1.0, OVERDUE_COLOR)
+    } else if target == 0 {
+        (0.0, color)
+    } else {
+        (active as f64 / target as f64, color)
+    }
+}
+
+fn is_pause_glyph(x: u32, y: u32) -> bool {
+    (12..20).contains(&y) && ((12..15).contains(&x) || (17..20).contains(&x))
+}
+
+pub(crate) fn format_clock(seconds: u64) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::{BreakConfig, TimerService};
+
+    fn pixel(icon: &[u8], x: u32, y: u32) -> Rgba {
+        let i = ((y * ICON_SIZE + x) * 4) as usize;
+        icon[i..i + 4].try_into().unwrap()
+    }
+
+    fn service() -> TimerService {
+        let mut config = BreakConfig::default();
+        config.microbreak_interval = 100;
+        config.rest_interval = 1000;
+        TimerService::new(config)
+    }
+
+    #[test]
+    fn test_rings_fill_with_progress() {
+        let mut service = service();
+        service.micro_active = 50;
+        let icon = render_icon(&service.get_status());
+
+        assert_eq!(icon.len(), (ICON_SIZE * ICON_SIZE * 4) as usize);
+        // Inner ring is filled on the right (first half turn) but not the left
+        assert_eq!(pixel(&icon, 16 + 8, 16), MICRO_COLOR);
+        assert_eq!(pixel(&icon, 16 - 9, 16), TRACK_COLOR);
+        // Outer ring has barely started
+        assert_eq!(pixel(&icon, 16 + 13, 16), TRACK_COLOR);
+        assert_eq!(pixel(&icon, 0, 0), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_overdue_and_suspended() {
+        let mut service = service();
+        service.micro_active = 101;
+        let icon = render_icon(&service.get_status());
+        assert_eq!(pixel(&icon, 16 - 9, 16), OVERDUE_COLOR);
+
+        service.set_mode(OperationMode::Suspended);
+        let icon = render_icon(&service.get_status());
+        assert_eq!(pixel(&icon, 16 - 9, 16), TRACK_COLOR);
+        assert_eq!(pixel(&icon, 13, 15), GLYPH_COLOR);
+    }
+
+    #[test]
+    fn test_tooltip() {
+        let mut service = service();
+        service.micro_active = 58;
+        service.rest_active = 200;
+        assert_eq!(tooltip(&service.get_status()), "Microbreak in 0:42, rest in 13:20");
+
+        service.micro_active = 101;
+        assert_eq!(tooltip(&service.get_status()), "Microbreak due, rest in 13:20");
+
+        service.take_break_now(BreakType::Rest);
+        assert_eq!(tooltip(&service.get_status()), "Rest break: 10:00 left");
+    }
+
+    #[test]
+    fn test_menu_entries_follow_state() {
+        let mut service = service();
+        service.micro_active = 10;
+        let menu = TrayMenu::from_service(&service);
+        let labels: Vec<_> = menu.breaks.iter().map(|entry| entry.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "Take microbreak now (in 2 min)",
+                "Skip microbreak",
+                "Take rest break now (in 17 min)",
+                "Skip rest break",
+            ]
+        );
+
+        // Due breaks can be postponed, locked ones only taken
+        service.micro_active = 101;
+        service.config.max_postpones = 3;
+        service.config.rest_strictness = crate::timer::BreakStrictness::Locked;
+        let menu = TrayMenu::from_service(&service);
+        let labels: Vec<_> = menu.breaks.iter().map(|entry| entry.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "Take microbreak now (due)",
+                "Postpone microbreak (3 left)",
+                "Skip microbreak",
+                "Take rest break now (in 17 min)",
+            ]
+        );
+
+        // Seconds of progress do not change the menu
+        service.micro_active = 0;
+        let before = TrayMenu::from_service(&service);
+        service.micro_active = 5;
+        assert_eq!(TrayMenu::from_service(&service), before);
+    }
+
+    #[test]
+    fn test_menu_ids_round_trip() {
+        let mut service = service();
+        service.micro_active = 101;
+        service.suspend_for(600);
+        let menu = TrayMenu::from_service(&service);
+
+        assert_eq!(menu.suspend[0].label, "Resume now (10 min left)");
+        assert_eq!(menu.suspend[3].label, "1 hour");
+        assert_eq!(parse_menu_id(&menu.suspend[4].id), Some(MenuAction::SuspendFor(7200)));
+        assert_eq!(parse_menu_id("resume"), Some(MenuAction::Resume));
+        assert_eq!(
+            parse_menu_id(&menu.breaks[1].id),
+            Some(MenuAction::Break(BreakType::Micro, BreakAction::Postpone))
+        );
+        assert_eq!(parse_menu_id("mode_normal"), None);
+        assert_eq!(parse_menu_id("show"), None);
+    }
+
+    #[test]
+    fn test_updates_only_on_change() {
+        let mut service = TimerService::new(BreakConfig::default());
+        let mut tray = TrayState::default();
+
+        let (icon, tooltip) = tray.update(&service.get_status());
+        assert!(icon.is_some() && tooltip.is_some());
+        assert_eq!(tray.update(&service.get_status()), (None, None));
+
+        // One second of progress moves the countdown but not the ring
+        service.micro_active = 1;
+        let (icon, tooltip) = tray.update(&service.get_status());
+        assert!(icon.is_none());
+        assert_eq!(tooltip.as_deref(), Some("Microbreak in 2:59, rest in 45:00"));
+    }
+}
@@ -29,16 +29,16 @@ describe("App", () => {
     (invoke as any).mockImplementation((cmd: string) => {
       if (cmd === "get_timer_state") {
         return Promise.resolve({
-          mode: "Normal",
-          micro_active: 0,
-          micro_target: 100,
-          micro_is_overdue: false,
-          rest_active: 0,
-          rest_target: 1000,
-          rest_is_overdue: false,
-          daily_usage: 0,
-          daily_limit: 10000,
-          current_idle: 0,
+          mode: "normal",
+          microActive: 0,
+          microTarget: 100,
+          microIsOverdue: false,
+          restActive: 0,
+          restTarget: 1000,
+          restIsOverdue: false,
+          dailyUsage: 0,
+          dailyLimit: 10000,
+          currentIdle: 0,
         });
       }
       if (cmd === "get_settings") return Promise.resolve({}); // needed for Settings
@@ -62,10 +62,10 @@ describe("App", () => {
       // Return just enough for components to not crash
       if (cmd === "get_timer_state")
         return Promise.resolve({
-          mode: "Normal",
-          micro_active: 0,
-          micro_target: 100,
-          rest_target: 1000,
+          mode: "normal",
+          microActive: 0,
+          microTarget: 100,
+          restTarget: 1000,
         });
       if (cmd === "get_settings") return Promise.resolve({});
       return Promise.resolve(null);
@@ -85,7 +85,7 @@ describe("App", () => {
   it("renders BreakOverlay if window label is overlay", async () => {
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     (getCurrentWindow as any).mockImplementation(() => ({
-      label: "overlay",
+      label: "overlay-0",
       hide: mock(() => Promise.resolve()),
     }));
 
@@ -93,11 +93,11 @@ describe("App", () => {
     (invoke as any).mockImplementation((cmd: string) => {
       if (cmd === "get_timer_state") {
         return Promise.resolve({
-          micro_is_overdue: true,
-          rest_is_overdue: false,
-          mode: "Normal",
-          micro_target: 100,
-          rest_target: 1000,
+          microIsOverdue: true,
+          restIsOverdue: false,
+          mode: "normal",
+          microTarget: 100,
+          restTarget: 1000,
         });
       }
       return Promise.resolve(null);
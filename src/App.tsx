@@ -19,9 +19,9 @@ function App() {
   const [isOverlay, setIsOverlay] = useState(false);
 
   useEffect(() => {
-    // Check if we are in the overlay window
+    // Check if we are in one of the per-monitor overlay windows
     try {
-      if (getCurrentWindow().label === "overlay") {
+      if (getCurrentWindow().label.startsWith("overlay-")) {
         // eslint-disable-next-line react-hooks/set-state-in-effect
         setIsOverlay(true);
       }
@@ -6,16 +6,16 @@ import type { TimerStatus } from "@/types";
 describe("TimerDisplay", () => {
   it("renders status correctly with circular progress", () => {
     const mockStatus: TimerStatus = {
-      daily_usage: 1200,
-      daily_limit: 3600,
-      micro_active: 300,
-      micro_target: 600,
-      micro_is_overdue: false,
-      rest_active: 0,
-      rest_target: 300,
-      rest_is_overdue: false,
-      current_idle: 5,
-      mode: "Normal",
+      dailyUsage: 1200,
+      dailyLimit: 3600,
+      microActive: 300,
+      microTarget: 600,
+      microIsOverdue: false,
+      restActive: 0,
+      restTarget: 300,
+      restIsOverdue: false,
+      currentIdle: 5,
+      mode: "normal",
     };
 
     const { container } = render(<TimerDisplay status={mockStatus} />);
@@ -29,9 +29,9 @@ describe("TimerDisplay", () => {
     expect(within(container).getByText("Daily limit")).toBeDefined();
 
     // Check that values are displayed in progress circles
-    expect(within(container).getByText("300")).toBeDefined(); // micro_active value
-    expect(within(container).getByText("/ 600s")).toBeDefined(); // micro_target
-    expect(within(container).getByText("1200")).toBeDefined(); // daily_usage value
+    expect(within(container).getByText("300")).toBeDefined(); // microActive value
+    expect(within(container).getByText("/ 600s")).toBeDefined(); // microTarget
+    expect(within(container).getByText("1200")).toBeDefined(); // dailyUsage value
 
     // Check current idle
     expect(within(container).getByText(/Current idle:/)).toBeDefined();
@@ -39,16 +39,16 @@ describe("TimerDisplay", () => {
 
   it("shows overdue status when breaks are overdue", () => {
     const mockStatus: TimerStatus = {
-      daily_usage: 100,
-      daily_limit: 3600,
-      micro_active: 700,
-      micro_target: 600,
-      micro_is_overdue: true,
-      rest_active: 400,
-      rest_target: 300,
-      rest_is_overdue: true,
-      current_idle: 0,
-      mode: "Normal",
+      dailyUsage: 100,
+      dailyLimit: 3600,
+      microActive: 700,
+      microTarget: 600,
+      microIsOverdue: true,
+      restActive: 400,
+      restTarget: 300,
+      restIsOverdue: true,
+      currentIdle: 0,
+      mode: "normal",
     };
 
     const { container } = render(<TimerDisplay status={mockStatus} />);
@@ -60,23 +60,23 @@ describe("TimerDisplay", () => {
 
   it("displays values in circular progress indicators", () => {
     const mockStatus: TimerStatus = {
-      daily_usage: 1800,
-      daily_limit: 3600,
-      micro_active: 300,
-      micro_target: 600,
-      micro_is_overdue: false,
-      rest_active: 150,
-      rest_target: 300,
-      rest_is_overdue: false,
-      current_idle: 10,
-      mode: "Quiet",
+      dailyUsage: 1800,
+      dailyLimit: 3600,
+      microActive: 300,
+      microTarget: 600,
+      microIsOverdue: false,
+      restActive: 150,
+      restTarget: 300,
+      restIsOverdue: false,
+      currentIdle: 10,
+      mode: "quiet",
     };
 
     const { container } = render(<TimerDisplay status={mockStatus} />);
 
     // Check that numeric values are displayed in the circles
-    expect(within(container).getByText("300")).toBeDefined(); // micro_active
-    expect(within(container).getByText("150")).toBeDefined(); // rest_active
-    expect(within(container).getByText("1800")).toBeDefined(); // daily_usage
+    expect(within(container).getByText("300")).toBeDefined(); // microActive
+    expect(within(container).getByText("150")).toBeDefined(); // restActive
+    expect(within(container).getByText("1800")).toBeDefined(); // dailyUsage
   });
 });
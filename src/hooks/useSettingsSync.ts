@@ -1,7 +1,7 @@
 import { useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { load } from "@tauri-apps/plugin-store";
-import type { BreakConfig } from "../types";
+import { migrateConfig } from "@/lib/settings";
 
 const SETTINGS_STORE = "settings.json";
 const SETTINGS_KEY = "break_config";
@@ -11,13 +11,13 @@ export function useSettingsSync() {
     async function syncSettings() {
       try {
         const store = await load(SETTINGS_STORE, { autoSave: false, defaults: {} });
-        const storedConfig = await store.get<BreakConfig>(SETTINGS_KEY);
+        const storedConfig = await store.get<Record<string, unknown>>(SETTINGS_KEY);
 
         if (storedConfig) {
           // Sync with backend immediately on startup
           // This ensures that even if the user doesn't visit the Settings page,
           // the backend uses the persisted settings.
-          await invoke("update_settings", { settings: storedConfig });
+          await invoke("update_settings", { settings: migrateConfig(storedConfig) });
           console.log("Global settings synced with backend on startup");
         } else {
           console.log("No stored settings found on startup, using backend defaults.");
@@ -20,16 +20,16 @@ describe.skip("useTimer", () => {
 
   it("fetches initial status on mount", async () => {
     const mockStatus = {
-      daily_usage: 100,
-      daily_limit: 28800,
-      micro_active: 50,
-      micro_target: 180,
-      micro_is_overdue: false,
-      rest_active: 200,
-      rest_target: 2700,
-      rest_is_overdue: false,
-      current_idle: 0,
-      mode: "Normal",
+      dailyUsage: 100,
+      dailyLimit: 28800,
+      microActive: 50,
+      microTarget: 180,
+      microIsOverdue: false,
+      restActive: 200,
+      restTarget: 2700,
+      restIsOverdue: false,
+      currentIdle: 0,
+      mode: "normal",
     };
 
     // Actually, let's just make sure we match what we check in toEqual
@@ -63,16 +63,16 @@ describe.skip("useTimer", () => {
     renderHook(() => useTimer());
 
     const newStatus = {
-      daily_usage: 101,
-      daily_limit: 28800,
-      micro_active: 51,
-      micro_target: 180,
-      micro_is_overdue: false,
-      rest_active: 201,
-      rest_target: 2700,
-      rest_is_overdue: false,
-      current_idle: 0,
-      mode: "Normal",
+      dailyUsage: 101,
+      dailyLimit: 28800,
+      microActive: 51,
+      microTarget: 180,
+      microIsOverdue: false,
+      restActive: 201,
+      restTarget: 2700,
+      restIsOverdue: false,
+      currentIdle: 0,
+      mode: "normal",
     };
 
     // Simulate event
@@ -0,0 +1,44 @@
+import type { BreakConfig, BreakInputPolicy, BreakStrictness, OperationMode } from "@/types";
+
+// Keys saved by versions that stored the settings in snake_case
+const LEGACY_KEYS: Record<string, keyof BreakConfig> = {
+  microbreak_interval: "microbreakInterval",
+  microbreak_duration: "microbreakDuration",
+  microbreak_enabled: "microbreakEnabled",
+  rest_interval: "restInterval",
+  rest_duration: "restDuration",
+  rest_enabled: "restEnabled",
+  daily_limit: "dailyLimit",
+  daily_enabled: "dailyEnabled",
+  warning_duration: "warningDuration",
+};
+
+export const MODE_LABELS: Record<OperationMode, string> = {
+  normal: "Normal",
+  quiet: "Quiet",
+  suspended: "Suspended",
+};
+
+export const STRICTNESS_LABELS: Record<BreakStrictness, string> = {
+  skippable: "Skippable",
+  postponable: "Postpone only",
+  locked: "Locked",
+};
+
+export const INPUT_POLICY_LABELS: Record<BreakInputPolicy, string> = {
+  ignore: "Keep counting down",
+  pause: "Pause the countdown",
+  restart: "Restart the countdown",
+};
+
+// Brings stored settings to the camelCase shape the backend serializes
+export function migrateConfig(stored: Record<string, unknown>): Partial<BreakConfig> {
+  const config: Record<string, unknown> = {};
+  for (const [key, value] of Object.entries(stored)) {
+    config[LEGACY_KEYS[key] ?? key] = value;
+  }
+  if (typeof config.mode === "string") {
+    config.mode = config.mode.toLowerCase();
+  }
+  return config as Partial<BreakConfig>;
+}
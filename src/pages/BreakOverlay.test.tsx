@@ -4,13 +4,31 @@ import { render, fireEvent, waitFor, within, act } from "@testing-library/react"
 import { BreakOverlay } from "./BreakOverlay";
 import { useTimer } from "@/hooks/useTimer";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { getCurrentWindow } from "@tauri-apps/api/window";
+import type { BreakPayload, BreakStrictness, BreakType } from "@/types";
 
 // Mock useTimer
 mock.module("@/hooks/useTimer", () => ({
   useTimer: mock(() => null),
 }));
 
+// Delivers a "break-payload" event as the backend sends it
+function sendPayload(breakType: BreakType, strictness: BreakStrictness) {
+  const payload: BreakPayload = {
+    breakType,
+    duration: 20,
+    strictness,
+    canPostpone: strictness !== "locked",
+    canSkip: strictness === "skippable",
+  };
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  (listen as any).mockImplementation((_event: string, handler: (event: { payload: BreakPayload }) => void) => {
+    handler({ payload });
+    return Promise.resolve(() => {});
+  });
+}
+
 describe("BreakOverlay", () => {
   beforeEach(() => {
     mock.restore();
@@ -19,35 +37,77 @@ describe("BreakOverlay", () => {
     (invoke as any).mockImplementation((cmd: string) => {
       if (cmd === "get_settings") {
         return Promise.resolve({
-          microbreak_duration: 20,
-          rest_duration: 300,
-          microbreak_enabled: true,
-          rest_enabled: true,
+          microbreakDuration: 20,
+          restDuration: 300,
+          microbreakEnabled: true,
+          restEnabled: true,
         });
       }
       return Promise.resolve();
     });
+    // eslint-disable-next-line @typescript-eslint/no-explicit-any
+    (listen as any).mockImplementation(() => Promise.resolve(() => {}));
   });
 
   it("renders initial state", () => {
+    sendPayload("micro", "skippable");
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     (useTimer as any).mockReturnValue({
-      micro_is_overdue: true,
-      rest_is_overdue: false,
+      microIsOverdue: true,
+      restIsOverdue: false,
     });
 
     const { baseElement } = render(<BreakOverlay />);
     const screen = within(baseElement);
 
     expect(screen.getByText("Microbreak Time!")).toBeInTheDocument();
+    expect(screen.getByText("Postpone")).toBeInTheDocument();
     expect(screen.getByText("Skip Break")).toBeInTheDocument();
   });
 
+  it("offers only postponing for a postponable break", async () => {
+    sendPayload("micro", "postponable");
+    const mockHide = mock(() => Promise.resolve());
+    // eslint-disable-next-line @typescript-eslint/no-explicit-any
+    (getCurrentWindow as any).mockImplementation(() => ({
+      hide: mockHide,
+    }));
+    // eslint-disable-next-line @typescript-eslint/no-explicit-any
+    (useTimer as any).mockReturnValue({
+      microIsOverdue: true,
+      restIsOverdue: false,
+    });
+
+    const { baseElement } = render(<BreakOverlay />);
+    const screen = within(baseElement);
+    expect(screen.queryByText("Skip Break")).toBeNull();
+    fireEvent.click(screen.getByText("Postpone"));
+
+    await waitFor(() => {
+      expect(invoke).toHaveBeenCalledWith("break_action", { breakType: "micro", action: "postpone" });
+      expect(mockHide).toHaveBeenCalled();
+    });
+  });
+
+  it("offers no buttons for a locked break", () => {
+    sendPayload("rest", "locked");
+    // eslint-disable-next-line @typescript-eslint/no-explicit-any
+    (useTimer as any).mockReturnValue({
+      microIsOverdue: false,
+      restIsOverdue: true,
+    });
+
+    const { baseElement } = render(<BreakOverlay />);
+    const screen = within(baseElement);
+    expect(screen.queryByText("Postpone")).toBeNull();
+    expect(screen.queryByText("Skip Break")).toBeNull();
+  });
+
   it("renders rest break message", () => {
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     (useTimer as any).mockReturnValue({
-      micro_is_overdue: false,
-      rest_is_overdue: true,
+      microIsOverdue: false,
+      restIsOverdue: true,
     });
 
     const { baseElement } = render(<BreakOverlay />);
@@ -58,8 +118,8 @@ describe("BreakOverlay", () => {
   it("displays progress bar with remaining time", async () => {
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     (useTimer as any).mockReturnValue({
-      micro_is_overdue: true,
-      rest_is_overdue: false,
+      microIsOverdue: true,
+      restIsOverdue: false,
     });
 
     const { baseElement } = render(<BreakOverlay />);
@@ -81,8 +141,8 @@ describe("BreakOverlay", () => {
   it("starts with a full countdown and full progress bar", async () => {
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     (useTimer as any).mockReturnValue({
-      micro_is_overdue: true,
-      rest_is_overdue: false,
+      microIsOverdue: true,
+      restIsOverdue: false,
     });
 
     const { baseElement } = render(<BreakOverlay />);
@@ -102,8 +162,8 @@ describe("BreakOverlay", () => {
   it("counts down and shrinks the progress bar over time", async () => {
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     (useTimer as any).mockReturnValue({
-      micro_is_overdue: true,
-      rest_is_overdue: false,
+      microIsOverdue: true,
+      restIsOverdue: false,
     });
 
     const { baseElement } = render(<BreakOverlay />);
@@ -130,8 +190,8 @@ describe("BreakOverlay", () => {
   it("fetches settings to get break duration", async () => {
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     (useTimer as any).mockReturnValue({
-      micro_is_overdue: true,
-      rest_is_overdue: false,
+      microIsOverdue: true,
+      restIsOverdue: false,
     });
 
     render(<BreakOverlay />);
@@ -147,8 +207,8 @@ describe("BreakOverlay", () => {
   it("increments progress over time", async () => {
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     (useTimer as any).mockReturnValue({
-      micro_is_overdue: true,
-      rest_is_overdue: false,
+      microIsOverdue: true,
+      restIsOverdue: false,
     });
 
     const { baseElement } = render(<BreakOverlay />);
@@ -173,6 +233,7 @@ describe("BreakOverlay", () => {
   });
 
   it("calls skip break handler for micro break", async () => {
+    sendPayload("micro", "skippable");
     const mockHide = mock(() => Promise.resolve());
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     (getCurrentWindow as any).mockImplementation(() => ({
@@ -181,8 +242,8 @@ describe("BreakOverlay", () => {
 
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     (useTimer as any).mockReturnValue({
-      micro_is_overdue: true,
-      rest_is_overdue: false,
+      microIsOverdue: true,
+      restIsOverdue: false,
     });
 
     const { baseElement } = render(<BreakOverlay />);
@@ -193,15 +254,76 @@ describe("BreakOverlay", () => {
 
     await waitFor(
       () => {
-        expect(invoke).toHaveBeenCalledWith("record_break_postponed", { breakType: "micro" });
-        expect(invoke).toHaveBeenCalledWith("reset_break", { breakType: "micro" });
+        expect(invoke).toHaveBeenCalledWith("break_action", { breakType: "micro", action: "skip" });
+        expect(invoke).not.toHaveBeenCalledWith("record_break_postponed", { breakType: "micro" });
         expect(mockHide).toHaveBeenCalled();
       },
       { timeout: 2000 }
     );
   });
 
+  it("shows the backend countdown from a get_timer_state payload", () => {
+    // Shaped as TimerStatus serializes in the backend
+    // eslint-disable-next-line @typescript-eslint/no-explicit-any
+    (useTimer as any).mockReturnValue({
+      dailyUsage: 600,
+      dailyLimit: 28800,
+      microActive: 181,
+      microTarget: 180,
+      microIsOverdue: true,
+      restActive: 181,
+      restTarget: 2700,
+      restIsOverdue: false,
+      currentIdle: 18,
+      activeBreak: {
+        breakType: "micro",
+        duration: 30,
+        remaining: 12,
+        inputDuringBreak: false,
+        prompts: 1,
+        elapsed: 18,
+        ignored: 0,
+        escalation: "overlay",
+      },
+      mode: "normal",
+      suspendRemaining: null,
+    });
+
+    const { baseElement } = render(<BreakOverlay />);
+    const screen = within(baseElement);
+    expect(screen.getByText("0:12 remaining")).toBeInTheDocument();
+  });
+
+  it("keeps the overlay when the skip is rejected", async () => {
+    sendPayload("micro", "skippable");
+    const mockHide = mock(() => Promise.resolve());
+    // eslint-disable-next-line @typescript-eslint/no-explicit-any
+    (getCurrentWindow as any).mockImplementation(() => ({
+      hide: mockHide,
+    }));
+    // eslint-disable-next-line @typescript-eslint/no-explicit-any
+    (invoke as any).mockImplementation((cmd: string) =>
+      cmd === "break_action" ? Promise.reject("Break is locked") : Promise.resolve()
+    );
+
+    // eslint-disable-next-line @typescript-eslint/no-explicit-any
+    (useTimer as any).mockReturnValue({
+      microIsOverdue: true,
+      restIsOverdue: false,
+    });
+
+    const { baseElement } = render(<BreakOverlay />);
+    const screen = within(baseElement);
+    fireEvent.click(screen.getByText("Skip Break"));
+
+    await waitFor(() => {
+      expect(invoke).toHaveBeenCalledWith("break_action", { breakType: "micro", action: "skip" });
+    });
+    expect(mockHide).not.toHaveBeenCalled();
+  });
+
   it("calls skip break handler for rest break", async () => {
+    sendPayload("rest", "skippable");
     const mockHide = mock(() => Promise.resolve());
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     (getCurrentWindow as any).mockImplementation(() => ({
@@ -210,8 +332,8 @@ describe("BreakOverlay", () => {
 
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     (useTimer as any).mockReturnValue({
-      micro_is_overdue: false,
-      rest_is_overdue: true,
+      microIsOverdue: false,
+      restIsOverdue: true,
     });
 
     const { baseElement } = render(<BreakOverlay />);
@@ -222,23 +344,23 @@ describe("BreakOverlay", () => {
 
     await waitFor(
       () => {
-        expect(invoke).toHaveBeenCalledWith("record_break_postponed", { breakType: "rest" });
-        expect(invoke).toHaveBeenCalledWith("reset_break", { breakType: "rest" });
+        expect(invoke).toHaveBeenCalledWith("break_action", { breakType: "rest", action: "skip" });
+        expect(invoke).not.toHaveBeenCalledWith("record_break_postponed", { breakType: "rest" });
         expect(mockHide).toHaveBeenCalled();
       },
       { timeout: 2000 }
     );
   });
 
-  it("uses rest_duration from settings for rest breaks", async () => {
+  it("uses restDuration from settings for rest breaks", async () => {
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     (invoke as any).mockImplementation((cmd: string) => {
       if (cmd === "get_settings") {
         return Promise.resolve({
-          microbreak_duration: 20,
-          rest_duration: 180, // 3 minutes
-          microbreak_enabled: true,
-          rest_enabled: true,
+          microbreakDuration: 20,
+          restDuration: 180, // 3 minutes
+          microbreakEnabled: true,
+          restEnabled: true,
         });
       }
       return Promise.resolve();
@@ -246,8 +368,8 @@ describe("BreakOverlay", () => {
 
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     (useTimer as any).mockReturnValue({
-      micro_is_overdue: false,
-      rest_is_overdue: true,
+      microIsOverdue: false,
+      restIsOverdue: true,
     });
 
     const { baseElement } = render(<BreakOverlay />);
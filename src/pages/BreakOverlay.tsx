@@ -1,16 +1,17 @@
 import { useEffect, useState, useRef, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 
 import { useTimer } from "@/hooks/useTimer";
-import type { BreakConfig } from "@/types";
+import type { BreakAction, BreakConfig, BreakPayload } from "@/types";
 
 export function BreakOverlay() {
   const status = useTimer();
   const [breakDuration, setBreakDuration] = useState(0);
   const [elapsedTime, setElapsedTime] = useState(0);
   const [settings, setSettings] = useState<BreakConfig | null>(null);
-  const submittedRef = useRef(false);
+  const [payload, setPayload] = useState<BreakPayload | null>(null);
 
   // Fetch settings on mount to get actual break durations
   useEffect(() => {
@@ -23,26 +24,40 @@ export function BreakOverlay() {
       });
   }, []);
 
+  // The backend sends the break being shown whenever the overlay changes
+  useEffect(() => {
+    const unlisten = listen<BreakPayload>("break-payload", (event) => {
+      setPayload(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => {});
+    };
+  }, []);
+
   // Compute break type and message from status (derived state, no need for useState)
   const { breakType, message, targetDuration } = (() => {
     if (!status) return { breakType: null, message: "Time for a break!", targetDuration: 0 };
-    if (status.micro_is_overdue && !status.rest_is_overdue) {
+    if (status.microIsOverdue && !status.restIsOverdue) {
       return {
         breakType: "micro" as const,
         message: "Microbreak Time!",
-        targetDuration: settings?.microbreak_duration || 20,
+        targetDuration: payload?.breakType === "micro" ? payload.duration : settings?.microbreakDuration || 20,
       };
     }
-    if (status.rest_is_overdue) {
+    if (status.restIsOverdue) {
       return {
         breakType: "rest" as const,
         message: "Rest Break Time!",
-        targetDuration: settings?.rest_duration || 300,
+        targetDuration: payload?.breakType === "rest" ? payload.duration : settings?.restDuration || 300,
       };
     }
     return { breakType: null, message: "Time for a break!", targetDuration: 0 };
   })();
 
+  // The backend owns and completes the countdown. The local timer only fills the display
+  // until the backend reports one.
+  const countdown = status?.activeBreak ?? null;
+
   // Track whether a break is active and its locked-in duration
   const prevBreakTypeRef = useRef<"micro" | "rest" | null>(null);
 
@@ -53,11 +68,9 @@ export function BreakOverlay() {
       // eslint-disable-next-line react-hooks/set-state-in-effect
       setBreakDuration(targetDuration);
       setElapsedTime(0);
-      submittedRef.current = false;
     } else if (!breakType && prevBreakTypeRef.current) {
       setBreakDuration(0);
       setElapsedTime(0);
-      submittedRef.current = false;
     }
     prevBreakTypeRef.current = breakType;
   }, [breakType, targetDuration]);
@@ -90,46 +103,29 @@ export function BreakOverlay() {
     }
   }, []);
 
-  const handleBreakComplete = useCallback(async () => {
-    if (!breakType || submittedRef.current) return;
-
-    submittedRef.current = true;
-    try {
-      await invoke("record_break_taken", { breakType });
-      await invoke("reset_break", { breakType });
-    } catch (error) {
-      console.error("Failed to record break completion:", error);
-      submittedRef.current = false; // Retry on failure
-    }
-    await closeWindow();
-  }, [breakType, closeWindow]);
-
-  const handleSkip = useCallback(async () => {
-    if (!breakType) {
-      // If we don't know the type, try to guess or just hide
+  const handleAction = useCallback(
+    async (action: BreakAction) => {
+      if (!breakType) {
+        // If we don't know the type, try to guess or just hide
+        await closeWindow();
+        return;
+      }
+
+      try {
+        // The backend rejects actions the break's strictness forbids, and counts the rest
+        await invoke("break_action", { breakType, action });
+      } catch (error) {
+        console.error(`Failed to ${action} break:`, error);
+        return;
+      }
       await closeWindow();
-      return;
-    }
-
-    try {
-      await invoke("record_break_postponed", { breakType });
-      await invoke("reset_break", { breakType });
-    } catch (error) {
-      console.error("Failed to record postponed break:", error);
-    }
-    await closeWindow();
-  }, [breakType, closeWindow]);
-
-  // Auto-complete break when duration is reached
-  useEffect(() => {
-    if (breakDuration > 0 && elapsedTime >= breakDuration) {
-      handleBreakComplete();
-    }
-  }, [elapsedTime, breakDuration, handleBreakComplete]);
+    },
+    [breakType, closeWindow]
+  );
 
-  const activeDuration = breakDuration > 0 ? breakDuration : targetDuration;
-  const progress = activeDuration > 0 ? Math.max(0, ((activeDuration - elapsedTime) / activeDuration) * 100) : 0;
-  const remainingSeconds = Math.max(0, activeDuration - elapsedTime);
+  const activeDuration = countdown ? countdown.duration : breakDuration > 0 ? breakDuration : targetDuration;
+  const remainingSeconds = countdown ? countdown.remaining : Math.max(0, activeDuration - elapsedTime);
+  const progress = activeDuration > 0 ? Math.max(0, (remainingSeconds / activeDuration) * 100) : 0;
   const remainingMinutes = Math.floor(remainingSeconds / 60);
   const remainingSecondsDisplay = remainingSeconds % 60;
 
@@ -156,9 +152,19 @@ export function BreakOverlay() {
         <p className="text-xs text-gray-400 mt-2 text-center">Break will complete automatically</p>
       </div>
 
-      <button onClick={handleSkip} className="px-6 py-2 bg-red-600 hover:bg-red-700 rounded-lg font-semibold transition-colors">
-        Skip Break
-      </button>
+      {/* Only the actions the break's strictness allows; a locked break offers none */}
+      <div className="flex gap-4">
+        {payload?.canPostpone && (
+          <button onClick={() => handleAction("postpone")} className="px-6 py-2 bg-gray-700 hover:bg-gray-600 rounded-lg font-semibold transition-colors">
+            Postpone
+          </button>
+        )}
+        {payload?.canSkip && (
+          <button onClick={() => handleAction("skip")} className="px-6 py-2 bg-red-600 hover:bg-red-700 rounded-lg font-semibold transition-colors">
+            Skip Break
+          </button>
+        )}
+      </div>
     </div>
   );
 }
@@ -26,16 +26,16 @@ describe("Settings", () => {
 
   it("loads settings from backend on mount when store is empty", async () => {
     const backendConfig = {
-      microbreak_interval: 180,
-      microbreak_duration: 30,
-      microbreak_enabled: true,
-      rest_interval: 2700,
-      rest_duration: 600,
-      rest_enabled: true,
-      daily_limit: 28800,
-      daily_enabled: true,
-      warning_duration: 30,
-      mode: "Normal",
+      microbreakInterval: 180,
+      microbreakDuration: 30,
+      microbreakEnabled: true,
+      restInterval: 2700,
+      restDuration: 600,
+      restEnabled: true,
+      dailyLimit: 28800,
+      dailyEnabled: true,
+      warningDuration: 30,
+      mode: "normal",
     };
 
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
@@ -57,16 +57,16 @@ describe("Settings", () => {
 
   it("persists settings to store when saved", async () => {
     const backendConfig = {
-      microbreak_interval: 100,
-      microbreak_duration: 20,
-      microbreak_enabled: true,
-      rest_interval: 2000,
-      rest_duration: 500,
-      rest_enabled: true,
-      daily_limit: 4000,
-      daily_enabled: false,
-      warning_duration: 30,
-      mode: "Normal",
+      microbreakInterval: 100,
+      microbreakDuration: 20,
+      microbreakEnabled: true,
+      restInterval: 2000,
+      restDuration: 500,
+      restEnabled: true,
+      dailyLimit: 4000,
+      dailyEnabled: false,
+      warningDuration: 30,
+      mode: "normal",
     };
 
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
@@ -87,22 +87,22 @@ describe("Settings", () => {
       // eslint-disable-next-line @typescript-eslint/no-explicit-any
       const stored = await mockStore.get<any>("break_config");
       expect(stored).toBeTruthy();
-      expect(stored.microbreak_interval).toBe(100);
+      expect(stored.microbreakInterval).toBe(100);
     });
   });
 
   it("loads settings from store on mount if available", async () => {
     const storedConfig = {
-      microbreak_interval: 250,
-      microbreak_duration: 45,
-      microbreak_enabled: true,
-      rest_interval: 3000,
-      rest_duration: 700,
-      rest_enabled: true,
-      daily_limit: 7200, // 2 hours
-      daily_enabled: true,
-      warning_duration: 30,
-      mode: "Quiet",
+      microbreakInterval: 250,
+      microbreakDuration: 45,
+      microbreakEnabled: true,
+      restInterval: 3000,
+      restDuration: 700,
+      restEnabled: true,
+      dailyLimit: 7200, // 2 hours
+      dailyEnabled: true,
+      warningDuration: 30,
+      mode: "quiet",
     };
 
     await mockStore.set("break_config", storedConfig);
@@ -113,8 +113,8 @@ describe("Settings", () => {
       if (cmd === "get_settings")
         return Promise.resolve({
           ...storedConfig,
-          microbreak_interval: 180,
-          mode: "Normal",
+          microbreakInterval: 180,
+          mode: "normal",
         });
       return Promise.resolve();
     });
@@ -133,6 +133,26 @@ describe("Settings", () => {
     });
   });
 
+  it("migrates settings saved in the old snake_case format", async () => {
+    await mockStore.set("break_config", { microbreak_interval: 250, daily_limit: 7200, mode: "Quiet" });
+    // eslint-disable-next-line @typescript-eslint/no-explicit-any
+    (invoke as any).mockResolvedValue(undefined);
+
+    const { baseElement } = render(<Settings />);
+    const screen = within(baseElement);
+
+    await waitFor(() => {
+      expect(screen.getByDisplayValue("250")).toBeInTheDocument();
+      expect(screen.getByDisplayValue("Quiet")).toBeInTheDocument();
+    });
+    // eslint-disable-next-line @typescript-eslint/no-explicit-any
+    const [, { settings }] = (invoke as any).mock.calls.find(([cmd]: [string]) => cmd === "update_settings");
+    expect(settings.microbreakInterval).toBe(250);
+    expect(settings.dailyLimit).toBe(7200);
+    expect(settings.mode).toBe("quiet");
+    expect(settings.microbreak_interval).toBeUndefined();
+  });
+
   it("has expected layout classes", async () => {
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     (invoke as any).mockResolvedValue({});
@@ -144,4 +164,63 @@ describe("Settings", () => {
       expect(grid).toBeInTheDocument();
     });
   });
+
+  it("binds no shortcuts until the suggested ones are chosen", async () => {
+    // eslint-disable-next-line @typescript-eslint/no-explicit-any
+    (invoke as any).mockImplementation((cmd: string) => {
+      if (cmd === "get_settings") {
+        return Promise.resolve({
+          microbreakInterval: 180,
+          shortcuts: { takeMicrobreak: null, takeRestBreak: null, postponeBreak: null, cycleMode: null },
+        });
+      }
+      return Promise.resolve();
+    });
+
+    const { baseElement } = render(<Settings />);
+    const screen = within(baseElement);
+    fireEvent.click(await screen.findByText("Use Suggested Shortcuts"));
+    fireEvent.submit(baseElement.querySelector("form")!);
+
+    await waitFor(async () => {
+      // eslint-disable-next-line @typescript-eslint/no-explicit-any
+      const stored = await mockStore.get<any>("break_config");
+      expect(stored.shortcuts.takeMicrobreak).toBe("CommandOrControl+Alt+M");
+      expect(stored.shortcuts.cycleMode).toBe("CommandOrControl+Alt+S");
+    });
+  });
+
+  it("saves nested settings and keeps fields it has no control for", async () => {
+    // eslint-disable-next-line @typescript-eslint/no-explicit-any
+    (invoke as any).mockImplementation((cmd: string) => {
+      if (cmd === "get_settings") {
+        return Promise.resolve({
+          microbreakInterval: 180,
+          defer: { media: false, inhibitors: true, maxDefer: 300 },
+          hooks: { breakStart: "playerctl pause", timeout: 10 },
+        });
+      }
+      return Promise.resolve();
+    });
+
+    const { baseElement } = render(<Settings />);
+    const screen = within(baseElement);
+    await screen.findByText("Save Settings");
+
+    fireEvent.change(baseElement.querySelector('select[name="restStrictness"]')!, { target: { value: "locked" } });
+    fireEvent.change(baseElement.querySelector('input[name="defer.maxDefer"]')!, { target: { value: "600" } });
+    fireEvent.click(baseElement.querySelector('input[name="defer.media"]')!);
+    fireEvent.change(baseElement.querySelector('input[name="calendar"]')!, { target: { value: "/home/me/work.ics" } });
+    fireEvent.submit(baseElement.querySelector("form")!);
+
+    await waitFor(async () => {
+      // eslint-disable-next-line @typescript-eslint/no-explicit-any
+      const stored = await mockStore.get<any>("break_config");
+      expect(stored.restStrictness).toBe("locked");
+      expect(stored.defer).toEqual({ media: true, inhibitors: true, maxDefer: 600 });
+      expect(stored.calendar).toBe("/home/me/work.ics");
+      expect(stored.locale).toBeNull();
+      expect(stored.hooks.breakStart).toBe("playerctl pause");
+    });
+  });
 });
@@ -1,24 +1,58 @@
 import { useEffect, useState, useCallback, type ChangeEvent, type FormEvent } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { load } from "@tauri-apps/plugin-store";
-import type { BreakConfig } from "../types";
+import { INPUT_POLICY_LABELS, MODE_LABELS, STRICTNESS_LABELS, migrateConfig } from "@/lib/settings";
+import type { BreakConfig, ShortcutConfig } from "../types";
 
 const SETTINGS_STORE = "settings.json";
 const SETTINGS_KEY = "break_config";
 
 const DEFAULT_CONFIG: BreakConfig = {
-  microbreak_interval: 1800,
-  microbreak_duration: 30,
-  microbreak_enabled: true,
-  rest_interval: 5400,
-  rest_duration: 600,
-  rest_enabled: true,
-  daily_limit: 28800,
-  daily_enabled: true,
-  warning_duration: 30,
-  mode: "Normal",
+  microbreakInterval: 1800,
+  microbreakDuration: 30,
+  microbreakEnabled: true,
+  microbreakStrictness: "skippable",
+  microbreakEscalation: { overlayAfter: 10, fullscreenAfter: 60 },
+  restInterval: 5400,
+  restDuration: 600,
+  restEnabled: true,
+  restStrictness: "skippable",
+  restEscalation: { overlayAfter: 30, fullscreenAfter: 120 },
+  restPartialCredit: 0,
+  coalesceWindow: 300,
+  breakInputPolicy: "pause",
+  apiSocket: false,
+  defer: { media: false, inhibitors: true, maxDefer: 300 },
+  calendar: null,
+  dailyLimit: 28800,
+  dailyEnabled: true,
+  warningDuration: 30,
+  mode: "normal",
+  locale: null,
 };
 
+// Offered to the user; nothing is bound until they choose to
+const SUGGESTED_SHORTCUTS: ShortcutConfig = {
+  takeMicrobreak: "CommandOrControl+Alt+M",
+  takeRestBreak: "CommandOrControl+Alt+R",
+  postponeBreak: "CommandOrControl+Alt+P",
+  cycleMode: "CommandOrControl+Alt+S",
+};
+
+const UNBOUND_SHORTCUTS: ShortcutConfig = {
+  takeMicrobreak: null,
+  takeRestBreak: null,
+  postponeBreak: null,
+  cycleMode: null,
+};
+
+const SHORTCUT_FIELDS: { name: keyof ShortcutConfig; label: string }[] = [
+  { name: "takeMicrobreak", label: "Take Microbreak" },
+  { name: "takeRestBreak", label: "Take Rest Break" },
+  { name: "postponeBreak", label: "Postpone Break" },
+  { name: "cycleMode", label: "Cycle Mode" },
+];
+
 export function Settings() {
   const [config, setConfig] = useState<BreakConfig | null>(null);
   const [loading, setLoading] = useState(true);
@@ -29,11 +63,11 @@ export function Settings() {
     try {
       // 1. Try to load from persistent store first
       const store = await load(SETTINGS_STORE, { autoSave: false, defaults: {} });
-      const storedConfig = await store.get<BreakConfig>(SETTINGS_KEY);
+      const storedConfig = await store.get<Record<string, unknown>>(SETTINGS_KEY);
 
       if (storedConfig) {
         console.log("Loaded settings from store:", storedConfig);
-        finalConfig = { ...finalConfig, ...storedConfig };
+        finalConfig = { ...finalConfig, ...migrateConfig(storedConfig) };
         setConfig(finalConfig);
         // Sync with backend to ensure it has the latest values
         await invoke("update_settings", { settings: finalConfig });
@@ -71,23 +105,29 @@ export function Settings() {
     const { name, type, checked, value } = e.target as HTMLInputElement;
     if (!config) return;
 
-    let newValue: string | number | boolean;
+    let newValue: string | number | boolean | null;
     if (type === "checkbox") {
       newValue = checked;
     } else if (type === "number") {
       const numVal = Number(value);
-      if (name === "daily_limit") {
+      if (name === "dailyLimit") {
         // UI shows hours, store in seconds
         newValue = numVal * 3600;
       } else {
         newValue = numVal;
       }
+    } else if (type === "text") {
+      // An empty calendar or locale means none
+      newValue = value === "" ? null : value;
     } else {
       newValue = value;
     }
 
-    // Update local state
-    const updatedConfig = { ...config, [name]: newValue };
+    // Names such as "defer.maxDefer" update a field of a nested setting
+    const [group, field] = name.split(".");
+    const updatedConfig = field
+      ? { ...config, [group]: { ...(config[group as keyof BreakConfig] as object), [field]: newValue } }
+      : { ...config, [name]: newValue };
     setConfig(updatedConfig);
 
     // If mode changed, immediately update the backend
@@ -100,6 +140,18 @@ export function Settings() {
     }
   };
 
+  const handleShortcutChange = (e: ChangeEvent<HTMLInputElement>) => {
+    if (!config) return;
+    const { name, value } = e.target;
+    const shortcuts = { ...UNBOUND_SHORTCUTS, ...config.shortcuts, [name]: value.trim() || null };
+    setConfig({ ...config, shortcuts });
+  };
+
+  const applySuggestedShortcuts = () => {
+    if (!config) return;
+    setConfig({ ...config, shortcuts: { ...SUGGESTED_SHORTCUTS } });
+  };
+
   const handleSubmit = async (e: FormEvent) => {
     e.preventDefault();
     if (!config) return;
@@ -131,8 +183,8 @@ export function Settings() {
             Microbreak Interval (s):
             <input
               type="number"
-              name="microbreak_interval"
-              value={config.microbreak_interval}
+              name="microbreakInterval"
+              value={config.microbreakInterval}
               onChange={handleChange}
               className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
             />
@@ -143,8 +195,8 @@ export function Settings() {
             Microbreak Duration (s):
             <input
               type="number"
-              name="microbreak_duration"
-              value={config.microbreak_duration}
+              name="microbreakDuration"
+              value={config.microbreakDuration}
               onChange={handleChange}
               className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
             />
@@ -152,10 +204,51 @@ export function Settings() {
         </div>
         <div className="md:col-span-2">
           <label className="inline-flex items-center text-gray-700 dark:text-gray-300">
-            <input type="checkbox" name="microbreak_enabled" checked={config.microbreak_enabled} onChange={handleChange} className="mr-2" />
+            <input type="checkbox" name="microbreakEnabled" checked={config.microbreakEnabled} onChange={handleChange} className="mr-2" />
             Enable Microbreaks
           </label>
         </div>
+        <div className="md:col-span-2">
+          <label className="block text-gray-700 dark:text-gray-300 mb-2">
+            Microbreak Strictness:
+            <select
+              name="microbreakStrictness"
+              value={config.microbreakStrictness}
+              onChange={handleChange}
+              className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
+            >
+              {Object.entries(STRICTNESS_LABELS).map(([value, label]) => (
+                <option key={value} value={value}>
+                  {label}
+                </option>
+              ))}
+            </select>
+          </label>
+        </div>
+        <div>
+          <label className="block text-gray-700 dark:text-gray-300 mb-2">
+            Microbreak Overlay After Input (s):
+            <input
+              type="number"
+              name="microbreakEscalation.overlayAfter"
+              value={config.microbreakEscalation.overlayAfter}
+              onChange={handleChange}
+              className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
+            />
+          </label>
+        </div>
+        <div>
+          <label className="block text-gray-700 dark:text-gray-300 mb-2">
+            Microbreak Fullscreen After Input (s):
+            <input
+              type="number"
+              name="microbreakEscalation.fullscreenAfter"
+              value={config.microbreakEscalation.fullscreenAfter}
+              onChange={handleChange}
+              className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
+            />
+          </label>
+        </div>
 
         <div className="border-t border-gray-200 dark:border-gray-700 col-span-1 md:col-span-2 my-2"></div>
 
@@ -164,8 +257,8 @@ export function Settings() {
             Rest Interval (s):
             <input
               type="number"
-              name="rest_interval"
-              value={config.rest_interval}
+              name="restInterval"
+              value={config.restInterval}
               onChange={handleChange}
               className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
             />
@@ -176,8 +269,8 @@ export function Settings() {
             Rest Duration (s):
             <input
               type="number"
-              name="rest_duration"
-              value={config.rest_duration}
+              name="restDuration"
+              value={config.restDuration}
               onChange={handleChange}
               className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
             />
@@ -185,10 +278,92 @@ export function Settings() {
         </div>
         <div className="md:col-span-2">
           <label className="inline-flex items-center text-gray-700 dark:text-gray-300">
-            <input type="checkbox" name="rest_enabled" checked={config.rest_enabled} onChange={handleChange} className="mr-2" />
+            <input type="checkbox" name="restEnabled" checked={config.restEnabled} onChange={handleChange} className="mr-2" />
             Enable Rest Breaks
           </label>
         </div>
+        <div className="md:col-span-2">
+          <label className="block text-gray-700 dark:text-gray-300 mb-2">
+            Rest Break Strictness:
+            <select
+              name="restStrictness"
+              value={config.restStrictness}
+              onChange={handleChange}
+              className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
+            >
+              {Object.entries(STRICTNESS_LABELS).map(([value, label]) => (
+                <option key={value} value={value}>
+                  {label}
+                </option>
+              ))}
+            </select>
+          </label>
+        </div>
+        <div>
+          <label className="block text-gray-700 dark:text-gray-300 mb-2">
+            Rest Overlay After Input (s):
+            <input
+              type="number"
+              name="restEscalation.overlayAfter"
+              value={config.restEscalation.overlayAfter}
+              onChange={handleChange}
+              className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
+            />
+          </label>
+        </div>
+        <div>
+          <label className="block text-gray-700 dark:text-gray-300 mb-2">
+            Rest Fullscreen After Input (s):
+            <input
+              type="number"
+              name="restEscalation.fullscreenAfter"
+              value={config.restEscalation.fullscreenAfter}
+              onChange={handleChange}
+              className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
+            />
+          </label>
+        </div>
+        <div>
+          <label className="block text-gray-700 dark:text-gray-300 mb-2">
+            Partial Rest Credit (%):
+            <input
+              type="number"
+              name="restPartialCredit"
+              value={config.restPartialCredit}
+              onChange={handleChange}
+              className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
+            />
+          </label>
+        </div>
+        <div>
+          <label className="block text-gray-700 dark:text-gray-300 mb-2">
+            Fold Microbreak Into Rest Due Within (s):
+            <input
+              type="number"
+              name="coalesceWindow"
+              value={config.coalesceWindow}
+              onChange={handleChange}
+              className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
+            />
+          </label>
+        </div>
+        <div className="md:col-span-2">
+          <label className="block text-gray-700 dark:text-gray-300 mb-2">
+            Input During a Break:
+            <select
+              name="breakInputPolicy"
+              value={config.breakInputPolicy}
+              onChange={handleChange}
+              className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
+            >
+              {Object.entries(INPUT_POLICY_LABELS).map(([value, label]) => (
+                <option key={value} value={value}>
+                  {label}
+                </option>
+              ))}
+            </select>
+          </label>
+        </div>
 
         <div className="border-t border-gray-200 dark:border-gray-700 col-span-1 md:col-span-2 my-2"></div>
 
@@ -197,8 +372,8 @@ export function Settings() {
             Daily Limit (hours):
             <input
               type="number"
-              name="daily_limit"
-              value={config.daily_limit / 3600}
+              name="dailyLimit"
+              value={config.dailyLimit / 3600}
               onChange={handleChange}
               step="0.1"
               className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
@@ -210,8 +385,8 @@ export function Settings() {
             Warning Duration (s):
             <input
               type="number"
-              name="warning_duration"
-              value={config.warning_duration}
+              name="warningDuration"
+              value={config.warningDuration}
               onChange={handleChange}
               className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
             />
@@ -219,13 +394,77 @@ export function Settings() {
         </div>
         <div className="md:col-span-2">
           <label className="inline-flex items-center text-gray-700 dark:text-gray-300">
-            <input type="checkbox" name="daily_enabled" checked={config.daily_enabled} onChange={handleChange} className="mr-2" />
+            <input type="checkbox" name="dailyEnabled" checked={config.dailyEnabled} onChange={handleChange} className="mr-2" />
             Enable Daily Limit
           </label>
         </div>
 
         <div className="border-t border-gray-200 dark:border-gray-700 col-span-1 md:col-span-2 my-2"></div>
 
+        <h3 className="md:col-span-2 font-semibold text-gray-900 dark:text-white">Meetings and Presentations</h3>
+        <div>
+          <label className="block text-gray-700 dark:text-gray-300 mb-2">
+            Calendar (.ics file or directory):
+            <input
+              type="text"
+              name="calendar"
+              value={config.calendar ?? ""}
+              onChange={handleChange}
+              placeholder="No calendar"
+              className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
+            />
+          </label>
+        </div>
+        <div>
+          <label className="block text-gray-700 dark:text-gray-300 mb-2">
+            Hold Breaks Back For At Most (s):
+            <input
+              type="number"
+              name="defer.maxDefer"
+              value={config.defer.maxDefer}
+              onChange={handleChange}
+              className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
+            />
+          </label>
+        </div>
+        <div className="md:col-span-2">
+          <label className="inline-flex items-center text-gray-700 dark:text-gray-300">
+            <input type="checkbox" name="defer.media" checked={config.defer.media} onChange={handleChange} className="mr-2" />
+            Hold breaks back while media plays (Linux)
+          </label>
+        </div>
+        <div className="md:col-span-2">
+          <label className="inline-flex items-center text-gray-700 dark:text-gray-300">
+            <input type="checkbox" name="defer.inhibitors" checked={config.defer.inhibitors} onChange={handleChange} className="mr-2" />
+            Hold breaks back while an application inhibits idle (Linux)
+          </label>
+        </div>
+
+        <div className="border-t border-gray-200 dark:border-gray-700 col-span-1 md:col-span-2 my-2"></div>
+
+        <h3 className="md:col-span-2 font-semibold text-gray-900 dark:text-white">Integration</h3>
+        <div>
+          <label className="block text-gray-700 dark:text-gray-300 mb-2">
+            Notification Language:
+            <input
+              type="text"
+              name="locale"
+              value={config.locale ?? ""}
+              onChange={handleChange}
+              placeholder="System language"
+              className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
+            />
+          </label>
+        </div>
+        <div className="md:col-span-2">
+          <label className="inline-flex items-center text-gray-700 dark:text-gray-300">
+            <input type="checkbox" name="apiSocket" checked={config.apiSocket} onChange={handleChange} className="mr-2" />
+            Serve the scripting API socket (Linux and macOS)
+          </label>
+        </div>
+
+        <div className="border-t border-gray-200 dark:border-gray-700 col-span-1 md:col-span-2 my-2"></div>
+
         <div className="md:col-span-2">
           <label className="block text-gray-700 dark:text-gray-300 mb-2">
             Mode:
@@ -235,12 +474,42 @@ export function Settings() {
               onChange={handleChange}
               className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
             >
-              <option value="Normal">Normal</option>
-              <option value="Quiet">Quiet</option>
-              <option value="Suspended">Suspended</option>
+              {Object.entries(MODE_LABELS).map(([mode, label]) => (
+                <option key={mode} value={mode}>
+                  {label}
+                </option>
+              ))}
             </select>
           </label>
         </div>
+
+        <div className="border-t border-gray-200 dark:border-gray-700 col-span-1 md:col-span-2 my-2"></div>
+
+        <div className="md:col-span-2 flex items-center justify-between">
+          <h3 className="font-semibold text-gray-900 dark:text-white">Global Shortcuts</h3>
+          <button
+            type="button"
+            onClick={applySuggestedShortcuts}
+            className="px-3 py-1 text-sm border border-gray-300 dark:border-gray-600 rounded-md text-gray-700 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-700"
+          >
+            Use Suggested Shortcuts
+          </button>
+        </div>
+        {SHORTCUT_FIELDS.map(({ name, label }) => (
+          <div key={name}>
+            <label className="block text-gray-700 dark:text-gray-300 mb-2">
+              {label}:
+              <input
+                type="text"
+                name={name}
+                value={config.shortcuts?.[name] ?? ""}
+                onChange={handleShortcutChange}
+                placeholder={SUGGESTED_SHORTCUTS[name] ?? ""}
+                className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500 dark:focus:ring-blue-400"
+              />
+            </label>
+          </div>
+        ))}
       </div>
       <button
         type="submit"
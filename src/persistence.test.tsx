@@ -18,16 +18,16 @@ describe("Global Settings Persistence", () => {
 
   it("syncs stored settings with backend on app startup", async () => {
     const storedConfig = {
-      microbreak_interval: 555,
-      microbreak_duration: 55,
-      microbreak_enabled: true,
-      rest_interval: 5555,
-      rest_duration: 555,
-      rest_enabled: true,
-      daily_limit: 55555,
-      daily_enabled: true,
-      warning_duration: 55,
-      mode: "Normal",
+      microbreakInterval: 555,
+      microbreakDuration: 55,
+      microbreakEnabled: true,
+      restInterval: 5555,
+      restDuration: 555,
+      restEnabled: true,
+      dailyLimit: 55555,
+      dailyEnabled: true,
+      warningDuration: 55,
+      mode: "normal",
     };
 
     // Mock store to return persisted settings
@@ -45,10 +45,10 @@ describe("Global Settings Persistence", () => {
     (invoke as any).mockImplementation((cmd: string) => {
       if (cmd === "get_timer_state") {
         return Promise.resolve({
-          mode: "Normal",
-          micro_active: 0,
-          micro_target: 100,
-          rest_target: 1000,
+          mode: "normal",
+          microActive: 0,
+          microTarget: 100,
+          restTarget: 1000,
         });
       }
       return Promise.resolve();
@@ -1,27 +1,118 @@
-export type OperationMode = "Normal" | "Quiet" | "Suspended";
+export type OperationMode = "normal" | "quiet" | "suspended";
 
+// Accelerators such as "CommandOrControl+Alt+M"; null leaves an action unbound
+export interface ShortcutConfig {
+  takeMicrobreak: string | null;
+  takeRestBreak: string | null;
+  postponeBreak: string | null;
+  cycleMode: string | null;
+}
+
+// Seconds of input during a break at which it escalates to the overlay, then fullscreen
+export interface EscalationConfig {
+  overlayAfter: number;
+  fullscreenAfter: number;
+}
+
+export type BreakInputPolicy = "ignore" | "pause" | "restart";
+
+export type FocusPolicy = "never" | "onShow" | "onEscalation";
+
+// Holding back break prompts while media plays or idle is inhibited (Linux)
+export interface DeferConfig {
+  media: boolean;
+  inhibitors: boolean;
+  maxDefer: number;
+}
+
+export interface InputConfig {
+  mouseButtons: boolean;
+  jitterThreshold: number;
+}
+
+// Shell commands run on break events; only set in the saved settings file
+export interface HookConfig {
+  warning: string | null;
+  breakStart: string | null;
+  breakEnd: string | null;
+  skipped: string | null;
+  dailyLimit: string | null;
+  timeout: number;
+  skipWhenSuspended: boolean;
+}
+
+// Optional fields have no control on the Settings page and are kept as loaded
 export interface BreakConfig {
-  microbreak_interval: number;
-  microbreak_duration: number;
-  microbreak_enabled: boolean;
-  rest_interval: number;
-  rest_duration: number;
-  rest_enabled: boolean;
-  daily_limit: number;
-  daily_enabled: boolean;
-  warning_duration: number;
+  microbreakInterval: number;
+  microbreakDuration: number;
+  microbreakEnabled: boolean;
+  microbreakStrictness: BreakStrictness;
+  microbreakEscalation: EscalationConfig;
+  restInterval: number;
+  restDuration: number;
+  restEnabled: boolean;
+  restStrictness: BreakStrictness;
+  restEscalation: EscalationConfig;
+  restPartialCredit: number;
+  coalesceWindow: number;
+  postponeDuration?: number;
+  maxPostpones?: number;
+  breakInputPolicy: BreakInputPolicy;
+  repromptInterval?: number;
+  overlayFocus?: FocusPolicy;
+  shortcuts?: ShortcutConfig;
+  apiSocket: boolean;
+  lockScreenIdle?: boolean;
+  defer: DeferConfig;
+  input?: InputConfig;
+  calendar: string | null;
+  hooks?: HookConfig;
+  dailyLimit: number;
+  dailyEnabled: boolean;
+  warningDuration: number;
   mode: OperationMode;
+  locale: string | null;
+}
+
+export type BreakType = "micro" | "rest";
+
+export type EscalationLevel = "notification" | "overlay" | "fullscreen";
+
+export type BreakStrictness = "skippable" | "postponable" | "locked";
+
+export interface BreakCountdown {
+  breakType: BreakType;
+  duration: number;
+  remaining: number;
+  inputDuringBreak: boolean;
+  prompts: number;
+  elapsed: number;
+  ignored: number;
+  escalation: EscalationLevel;
+}
+
+export type BreakAction = "takeNow" | "postpone" | "skip";
+
+// Sent by the backend as "break-payload"; the flags say which buttons the break allows
+export interface BreakPayload {
+  breakType: BreakType;
+  duration: number;
+  strictness: BreakStrictness;
+  canPostpone: boolean;
+  canSkip: boolean;
 }
 
 export interface TimerStatus {
-  daily_usage: number;
-  daily_limit: number;
-  micro_active: number;
-  micro_target: number;
-  micro_is_overdue: boolean;
-  rest_active: number;
-  rest_target: number;
-  rest_is_overdue: boolean;
-  current_idle: number;
+  dailyUsage: number;
+  dailyLimit: number;
+  microActive: number;
+  microTarget: number;
+  microIsOverdue: boolean;
+  restActive: number;
+  restTarget: number;
+  restIsOverdue: boolean;
+  currentIdle: number;
+  activeBreak?: BreakCountdown | null;
   mode: OperationMode;
+  suspendRemaining?: number | null;
 }